@@ -0,0 +1,25 @@
+#!/bin/bash
+# Run the search benchmark on an earlier revision and on this tree, side by
+# side: `benches/compare.sh [revision]`. The default revision is the one
+# before the search state moved onto the dense symbol alphabet.
+set -e
+
+cd "$(dirname "$0")/.."
+BASELINE=${1:-5590c0f^}
+WORKTREE=$(mktemp -d)
+trap 'git worktree remove --force "$WORKTREE"' EXIT
+
+# The benchmark only uses API that older revisions already have
+git worktree add --detach --quiet "$WORKTREE" "$BASELINE"
+mkdir -p "$WORKTREE/wasm/benches"
+cp benches/search.rs "$WORKTREE/wasm/benches/search.rs"
+if ! grep -q '^\[\[bench\]\]' "$WORKTREE/wasm/Cargo.toml"; then
+    printf '\n[[bench]]\nname = "search"\nharness = false\n' >> "$WORKTREE/wasm/Cargo.toml"
+fi
+
+(cd "$WORKTREE/wasm" && cargo bench --quiet --bench search) > "$WORKTREE/before.txt"
+cargo bench --quiet --bench search > "$WORKTREE/after.txt"
+
+echo "Board              before ($BASELINE)                       after"
+paste -d '\t' "$WORKTREE/before.txt" "$WORKTREE/after.txt" \
+    | awk -F '\t' '{ printf "%-16s %-40s %s\n", substr($1, 1, 16), substr($1, 18), substr($2, 18) }'
@@ -0,0 +1,52 @@
+// Search timings on standard boards: `cargo bench --bench search`
+//
+// Uses only std::time so it runs on stable without extra dependencies, and
+// only long-standing API, so `benches/compare.sh` can also run it on earlier
+// revisions.
+
+use std::time::{Duration, Instant};
+
+use sumzle_solver::{Constraints, RuleSet, SumzleSolver};
+
+struct Board {
+    name: &'static str,
+    length: usize,
+    rules: RuleSet,
+    rows: &'static [&'static str],
+}
+
+fn boards() -> Vec<Board> {
+    vec![
+        Board { name: "6, empty", length: 6, rules: RuleSet::classic(), rows: &[] },
+        Board { name: "6, game rules", length: 6, rules: RuleSet::game_compatible(), rows: &[] },
+        Board { name: "7, one row", length: 7, rules: RuleSet::classic(), rows: &["12+3=15:GYBBBGG"] },
+        Board { name: "7, no '='", length: 7, rules: RuleSet::classic(), rows: &["1+2+3>4:BBBBBBB"] },
+        Board { name: "8, one row", length: 8, rules: RuleSet::classic(), rows: &["9*8-7=65:YBBBBGBB"] },
+        Board { name: "8, two rows", length: 8, rules: RuleSet::classic(), rows: &["9*8-7=65:YBBBBGBB", "10+24=34:YBGYYGYY"] },
+    ]
+}
+
+fn main() {
+    for board in boards() {
+        let constraints = Constraints::from_compact(board.rows).unwrap();
+
+        // Run until at least a second has been spent, keeping the fastest time
+        let mut best = Duration::MAX;
+        let mut total = Duration::ZERO;
+        let mut runs = 0;
+        let mut found = 0;
+        while total < Duration::from_secs(1) || runs < 3 {
+            // A fresh solver each run, so the result cache is not measured
+            let solver = SumzleSolver::with_rules(board.length, 30, board.rules);
+            let start = Instant::now();
+            let (results, _) = solver.search_counted(&constraints).unwrap();
+            let elapsed = start.elapsed();
+            found = results.len();
+            best = best.min(elapsed);
+            total += elapsed;
+            runs += 1;
+        }
+
+        println!("{:<16} {:>7} results  {:>10.2?} best of {}", board.name, found, best, runs);
+    }
+}
@@ -1,5 +1,20 @@
 let wasm;
 
+function addToExternrefTable0(obj) {
+    const idx = wasm.__externref_table_alloc();
+    wasm.__wbindgen_export_2.set(idx, obj);
+    return idx;
+}
+
+function handleError(f, args) {
+    try {
+        return f.apply(this, args);
+    } catch (e) {
+        const idx = addToExternrefTable0(e);
+        wasm.__wbindgen_exn_store(idx);
+    }
+}
+
 const cachedTextDecoder = (typeof TextDecoder !== 'undefined' ? new TextDecoder('utf-8', { ignoreBOM: true, fatal: true }) : { decode: () => { throw Error('TextDecoder not available') } } );
 
 if (typeof TextDecoder !== 'undefined') { cachedTextDecoder.decode(); };
@@ -18,6 +33,71 @@ function getStringFromWasm0(ptr, len) {
     return cachedTextDecoder.decode(getUint8ArrayMemory0().subarray(ptr, ptr + len));
 }
 
+function debugString(val) {
+    // primitive types
+    const type = typeof val;
+    if (type == 'number' || type == 'boolean' || val == null) {
+        return  `${val}`;
+    }
+    if (type == 'string') {
+        return `"${val}"`;
+    }
+    if (type == 'symbol') {
+        const description = val.description;
+        if (description == null) {
+            return 'Symbol';
+        } else {
+            return `Symbol(${description})`;
+        }
+    }
+    if (type == 'function') {
+        const name = val.name;
+        if (typeof name == 'string' && name.length > 0) {
+            return `Function(${name})`;
+        } else {
+            return 'Function';
+        }
+    }
+    // objects
+    if (Array.isArray(val)) {
+        const length = val.length;
+        let debug = '[';
+        if (length > 0) {
+            debug += debugString(val[0]);
+        }
+        for(let i = 1; i < length; i++) {
+            debug += ', ' + debugString(val[i]);
+        }
+        debug += ']';
+        return debug;
+    }
+    // Test for built-in
+    const builtInMatches = /\[object ([^\]]+)\]/.exec(toString.call(val));
+    let className;
+    if (builtInMatches && builtInMatches.length > 1) {
+        className = builtInMatches[1];
+    } else {
+        // Failed to match the standard '[object ClassName]'
+        return toString.call(val);
+    }
+    if (className == 'Object') {
+        // we're a user defined class or Object
+        // JSON.stringify avoids problems with cycles, and is generally much
+        // easier than looping through ownProperties of `val`.
+        try {
+            return 'Object(' + JSON.stringify(val) + ')';
+        } catch (_) {
+            return 'Object';
+        }
+    }
+    // errors
+    if (val instanceof Error) {
+        return `${val.name}: ${val.message}\n${val.stack}`;
+    }
+    // TODO we could test for more things here, like `Set`s and `Map`s.
+    return className;
+}
+
 let WASM_VECTOR_LEN = 0;
 
 const cachedTextEncoder = (typeof TextEncoder !== 'undefined' ? new TextEncoder('utf-8') : { encode: () => { throw Error('TextEncoder not available') } } );
@@ -74,12 +154,304 @@ function passStringToWasm0(arg, malloc, realloc) {
     return ptr;
 }
 
+let cachedDataViewMemory0 = null;
+
+function getDataViewMemory0() {
+    if (cachedDataViewMemory0 === null || cachedDataViewMemory0.buffer.detached === true || (cachedDataViewMemory0.buffer.detached === undefined && cachedDataViewMemory0.buffer !== wasm.memory.buffer)) {
+        cachedDataViewMemory0 = new DataView(wasm.memory.buffer);
+    }
+    return cachedDataViewMemory0;
+}
+
+function takeFromExternrefTable0(idx) {
+    const value = wasm.__wbindgen_export_2.get(idx);
+    wasm.__externref_table_dealloc(idx);
+    return value;
+}
+
+function _assertChar(c) {
+    if (typeof(c) === 'number' && (c >= 0x110000 || (c >= 0xD800 && c < 0xE000))) throw new Error(`expected a valid Unicode scalar value, found ${c}`);
+}
+
+function passArray8ToWasm0(arg, malloc) {
+    const ptr = malloc(arg.length * 1, 1) >>> 0;
+    getUint8ArrayMemory0().set(arg, ptr / 1);
+    WASM_VECTOR_LEN = arg.length;
+    return ptr;
+}
+/**
+ * @param {string} date
+ * @param {string} difficulty
+ * @returns {string}
+ */
+export function daily_puzzle(date, difficulty) {
+    let deferred4_0;
+    let deferred4_1;
+    try {
+        const ptr0 = passStringToWasm0(date, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(difficulty, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.daily_puzzle(ptr0, len0, ptr1, len1);
+        var ptr3 = ret[0];
+        var len3 = ret[1];
+        if (ret[3]) {
+            ptr3 = 0; len3 = 0;
+            throw takeFromExternrefTable0(ret[2]);
+        }
+        deferred4_0 = ptr3;
+        deferred4_1 = len3;
+        return getStringFromWasm0(ptr3, len3);
+    } finally {
+        wasm.__wbindgen_free(deferred4_0, deferred4_1, 1);
+    }
+}
+
+const GameSessionFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_gamesession_free(ptr >>> 0, 1));
+
+export class GameSession {
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        GameSessionFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_gamesession_free(ptr, 0);
+    }
+    /**
+     * @param {string} target
+     * @param {string} difficulty
+     * @param {boolean} one_try
+     * @param {boolean} sudden_death
+     */
+    constructor(target, difficulty, one_try, sudden_death) {
+        const ptr0 = passStringToWasm0(target, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(difficulty, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.gamesession_new(ptr0, len0, ptr1, len1, one_try, sudden_death);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        this.__wbg_ptr = ret[0] >>> 0;
+        GameSessionFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * @param {string} input
+     * @returns {any}
+     */
+    hint(input) {
+        const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.gamesession_hint(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * @param {number} elapsed_seconds
+     * @returns {string}
+     */
+    tick(elapsed_seconds) {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.gamesession_tick(this.__wbg_ptr, elapsed_seconds);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
+     * @returns {any}
+     */
+    state() {
+        const ret = wasm.gamesession_state(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {string}
+     */
+    status() {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.gamesession_status(this.__wbg_ptr);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
+     * @param {string} guess
+     * @returns {any}
+     */
+    submit(guess) {
+        const ptr0 = passStringToWasm0(guess, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.gamesession_submit(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * @returns {string}
+     */
+    target() {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.gamesession_target(this.__wbg_ptr);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+    abandon() {
+        wasm.gamesession_abandon(this.__wbg_ptr);
+    }
+    /**
+     * @param {number} seed
+     */
+    set_seed(seed) {
+        wasm.gamesession_set_seed(this.__wbg_ptr, seed);
+    }
+    /**
+     * @param {string} key
+     * @returns {any}
+     */
+    key_state(key) {
+        const char0 = key.codePointAt(0);
+        _assertChar(char0);
+        const ret = wasm.gamesession_key_state(this.__wbg_ptr, char0);
+        return ret;
+    }
+}
+
+const PuzzleGraderFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_puzzlegrader_free(ptr >>> 0, 1));
+
+export class PuzzleGrader {
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        PuzzleGraderFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_puzzlegrader_free(ptr, 0);
+    }
+    /**
+     * @param {Uint8Array} bytes
+     */
+    add_dictionary(bytes) {
+        const ptr0 = passArray8ToWasm0(bytes, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.puzzlegrader_add_dictionary(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {string} target
+     * @returns {any}
+     */
+    rate_difficulty(target) {
+        const ptr0 = passStringToWasm0(target, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.puzzlegrader_rate_difficulty(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * @param {number} max_operand_value
+     */
+    constructor(max_operand_value) {
+        const ret = wasm.puzzlegrader_new(max_operand_value);
+        this.__wbg_ptr = ret >>> 0;
+        PuzzleGraderFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+}
+
+const SearchSessionFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_searchsession_free(ptr >>> 0, 1));
+
+export class SearchSession {
+
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(SearchSession.prototype);
+        obj.__wbg_ptr = ptr;
+        SearchSessionFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        SearchSessionFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_searchsession_free(ptr, 0);
+    }
+    /**
+     * @param {number} budget
+     * @returns {any}
+     */
+    step(budget) {
+        const ret = wasm.searchsession_step(this.__wbg_ptr, budget);
+        return ret;
+    }
+    cancel() {
+        wasm.searchsession_cancel(this.__wbg_ptr);
+    }
+    /**
+     * @returns {boolean}
+     */
+    is_done() {
+        const ret = wasm.searchsession_is_done(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {number}
+     */
+    searched() {
+        const ret = wasm.searchsession_searched(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+}
+
 const SumzleSolverFinalization = (typeof FinalizationRegistry === 'undefined')
     ? { register: () => {}, unregister: () => {} }
     : new FinalizationRegistry(ptr => wasm.__wbg_sumzlesolver_free(ptr >>> 0, 1));
 
 export class SumzleSolver {
 
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(SumzleSolver.prototype);
+        obj.__wbg_ptr = ptr;
+        SumzleSolverFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+
     __destroy_into_raw() {
         const ptr = this.__wbg_ptr;
         this.__wbg_ptr = 0;
@@ -91,25 +463,88 @@ export class SumzleSolver {
         const ptr = this.__destroy_into_raw();
         wasm.__wbg_sumzlesolver_free(ptr, 0);
     }
+    /**
+     * @param {string} constraints_json
+     * @param {number} shard_idx
+     * @param {number} shard_count
+     * @returns {any}
+     */
+    search_shard(constraints_json, shard_idx, shard_count) {
+        const ptr0 = passStringToWasm0(constraints_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sumzlesolver_search_shard(this.__wbg_ptr, ptr0, len0, shard_idx, shard_count);
+        return ret;
+    }
+    /**
+     * @param {string} constraints_json
+     * @returns {SearchSession}
+     */
+    start_search(constraints_json) {
+        const ptr0 = passStringToWasm0(constraints_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sumzlesolver_start_search(this.__wbg_ptr, ptr0, len0);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return SearchSession.__wrap(ret[0]);
+    }
     /**
      * @param {number} length
      * @param {number} max_operand_value
+     * @param {string} symbols
+     * @returns {SumzleSolver}
      */
-    constructor(length, max_operand_value) {
-        const ret = wasm.sumzlesolver_new(length, max_operand_value);
-        this.__wbg_ptr = ret >>> 0;
-        SumzleSolverFinalization.register(this, this.__wbg_ptr, this);
-        return this;
+    static with_symbols(length, max_operand_value, symbols) {
+        const ptr0 = passStringToWasm0(symbols, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sumzlesolver_with_symbols(length, max_operand_value, ptr0, len0);
+        return SumzleSolver.__wrap(ret);
     }
     /**
-     * @param {string} expr
-     * @returns {number | undefined}
+     * @param {string} expression
+     * @returns {any}
      */
-    evaluate_expression(expr) {
-        const ptr0 = passStringToWasm0(expr, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    check_equation(expression) {
+        const ptr0 = passStringToWasm0(expression, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
         const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.sumzlesolver_evaluate_expression(this.__wbg_ptr, ptr0, len0);
-        return ret === 0x100000001 ? undefined : ret;
+        const ret = wasm.sumzlesolver_check_equation(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * @param {Uint8Array} bytes
+     */
+    load_dictionary(bytes) {
+        const ptr0 = passArray8ToWasm0(bytes, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sumzlesolver_load_dictionary(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {string} constraints_json
+     * @param {number} top_n
+     * @param {boolean} include_non_candidates
+     * @param {string} mode
+     * @returns {any}
+     */
+    recommend_guess(constraints_json, top_n, include_non_candidates, mode) {
+        const ptr0 = passStringToWasm0(constraints_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(mode, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.sumzlesolver_recommend_guess(this.__wbg_ptr, ptr0, len0, top_n, include_non_candidates, ptr1, len1);
+        return ret;
+    }
+    /**
+     * @param {string} profile
+     * @returns {boolean}
+     */
+    set_rule_profile(profile) {
+        const ptr0 = passStringToWasm0(profile, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sumzlesolver_set_rule_profile(this.__wbg_ptr, ptr0, len0);
+        return ret !== 0;
     }
     /**
      * @param {string} expression
@@ -121,6 +556,26 @@ export class SumzleSolver {
         const ret = wasm.sumzlesolver_is_valid_solution(this.__wbg_ptr, ptr0, len0);
         return ret !== 0;
     }
+    /**
+     * @param {string} expr
+     * @returns {number | undefined}
+     */
+    evaluate_expression(expr) {
+        const ptr0 = passStringToWasm0(expr, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sumzlesolver_evaluate_expression(this.__wbg_ptr, ptr0, len0);
+        return ret === 0x100000001 ? undefined : ret;
+    }
+    /**
+     * @param {number} length
+     * @param {number} max_operand_value
+     */
+    constructor(length, max_operand_value) {
+        const ret = wasm.sumzlesolver_new(length, max_operand_value);
+        this.__wbg_ptr = ret >>> 0;
+        SumzleSolverFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
     /**
      * @param {string} constraints_json
      * @returns {any}
@@ -131,6 +586,16 @@ export class SumzleSolver {
         const ret = wasm.sumzlesolver_search(this.__wbg_ptr, ptr0, len0);
         return ret;
     }
+    /**
+     * @param {string} expr
+     * @returns {any}
+     */
+    evaluate(expr) {
+        const ptr0 = passStringToWasm0(expr, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sumzlesolver_evaluate(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
 }
 
 async function __wbg_load(module, imports) {
@@ -167,14 +632,47 @@ async function __wbg_load(module, imports) {
 function __wbg_get_imports() {
     const imports = {};
     imports.wbg = {};
+    imports.wbg.__wbg_fromCodePoint_f37c25c172f2e8b5 = function() { return handleError(function (arg0) {
+        const ret = String.fromCodePoint(arg0 >>> 0);
+        return ret;
+    }, arguments) };
     imports.wbg.__wbg_log_b1f0318c1f7d5732 = function(arg0, arg1) {
         console.log(getStringFromWasm0(arg0, arg1));
     };
     imports.wbg.__wbg_log_c222819a41e063d3 = function(arg0) {
         console.log(arg0);
     };
+    imports.wbg.__wbg_new_405e22f390576ce2 = function() {
+        const ret = new Object();
+        return ret;
+    };
+    imports.wbg.__wbg_new_78feb108b6472713 = function() {
+        const ret = new Array();
+        return ret;
+    };
+    imports.wbg.__wbg_set_37837023f3d740e8 = function(arg0, arg1, arg2) {
+        arg0[arg1 >>> 0] = arg2;
+    };
+    imports.wbg.__wbg_set_3f1d0b984ed272ed = function(arg0, arg1, arg2) {
+        arg0[arg1] = arg2;
+    };
+    imports.wbg.__wbindgen_bigint_from_u64 = function(arg0) {
+        const ret = BigInt.asUintN(64, arg0);
+        return ret;
+    };
+    imports.wbg.__wbindgen_debug_string = function(arg0, arg1) {
+        const ret = debugString(arg1);
+        const ptr1 = passStringToWasm0(ret, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
+        getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
+    };
+    imports.wbg.__wbindgen_error_new = function(arg0, arg1) {
+        const ret = new Error(getStringFromWasm0(arg0, arg1));
+        return ret;
+    };
     imports.wbg.__wbindgen_init_externref_table = function() {
-        const table = wasm.__wbindgen_export_0;
+        const table = wasm.__wbindgen_export_2;
         const offset = table.grow(4);
         table.set(0, undefined);
         table.set(offset + 0, undefined);
@@ -183,8 +681,8 @@ function __wbg_get_imports() {
         table.set(offset + 3, false);
         ;
     };
-    imports.wbg.__wbindgen_json_parse = function(arg0, arg1) {
-        const ret = JSON.parse(getStringFromWasm0(arg0, arg1));
+    imports.wbg.__wbindgen_number_new = function(arg0) {
+        const ret = arg0;
         return ret;
     };
     imports.wbg.__wbindgen_string_new = function(arg0, arg1) {
@@ -205,6 +703,7 @@ function __wbg_init_memory(imports, memory) {
 function __wbg_finalize_init(instance, module) {
     wasm = instance.exports;
     __wbg_init.__wbindgen_wasm_module = module;
+    cachedDataViewMemory0 = null;
     cachedUint8ArrayMemory0 = null;
 
 
@@ -0,0 +1,116 @@
+// Dense indexing of the 24 symbols an equation can use, so the search can
+// keep character sets and counts in bitmasks and fixed-size arrays
+
+use std::fmt;
+
+pub const SYMBOL_COUNT: usize = 24;
+
+pub const SYMBOLS: [char; SYMBOL_COUNT] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+    '+', '-', '*', '/', '%', '^', '=', '(', ')', '!', '[', ']', '>', 'A',
+];
+
+pub fn symbol_index(c: char) -> Option<usize> {
+    Some(match c {
+        '0'..='9' => c as usize - '0' as usize,
+        '+' => 10,
+        '-' => 11,
+        '*' => 12,
+        '/' => 13,
+        '%' => 14,
+        '^' => 15,
+        '=' => 16,
+        '(' => 17,
+        ')' => 18,
+        '!' => 19,
+        '[' => 20,
+        ']' => 21,
+        '>' => 22,
+        'A' => 23,
+        _ => return None,
+    })
+}
+
+// Set of symbols as a bitmask; characters outside the alphabet are never members
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CharSet(u32);
+
+impl CharSet {
+    pub const EMPTY: CharSet = CharSet(0);
+    pub const ALL: CharSet = CharSet((1 << SYMBOL_COUNT) - 1);
+
+    pub fn contains(&self, c: char) -> bool {
+        symbol_index(c).is_some_and(|i| self.0 & (1 << i) != 0)
+    }
+
+    pub fn insert(&mut self, c: char) {
+        if let Some(i) = symbol_index(c) {
+            self.0 |= 1 << i;
+        }
+    }
+
+    pub fn remove(&mut self, c: char) {
+        if let Some(i) = symbol_index(c) {
+            self.0 &= !(1 << i);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    // One bit per symbol, in `SYMBOLS` order
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn from_bits(bits: u32) -> Self {
+        CharSet(bits & Self::ALL.0)
+    }
+
+    pub fn is_superset(&self, other: CharSet) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        SYMBOLS.iter().copied().filter(|&c| self.contains(c))
+    }
+}
+
+impl FromIterator<char> for CharSet {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut set = CharSet::EMPTY;
+        for c in iter {
+            set.insert(c);
+        }
+        set
+    }
+}
+
+impl fmt::Debug for CharSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+// Number of occurrences of each symbol
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct CharCounts([u8; SYMBOL_COUNT]);
+
+impl CharCounts {
+    pub fn get(&self, c: char) -> usize {
+        symbol_index(c).map_or(0, |i| self.0[i] as usize)
+    }
+
+    pub fn add(&mut self, c: char) {
+        if let Some(i) = symbol_index(c) {
+            self.0[i] += 1;
+        }
+    }
+
+    pub fn remove(&mut self, c: char) {
+        if let Some(i) = symbol_index(c) {
+            self.0[i] -= 1;
+        }
+    }
+}
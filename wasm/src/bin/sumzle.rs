@@ -0,0 +1,254 @@
+// Command-line Sumzle solver
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use sumzle_solver::recommend::{rank_guesses, RecommendMode};
+use sumzle_solver::alphabet::symbol_index;
+use sumzle_solver::{CharSet, Constraints, Dictionary, RuleSet, SumzleSolver};
+
+const USAGE: &str = "\
+Usage: sumzle [OPTIONS] [ROW...]
+       sumzle dict --length N [--max N] [--rules NAME] [--symbols SET] --output FILE
+
+Rows are written as guess:states, e.g. 12+3=15:GYBBBGG, where G is correct,
+Y is present and B (or .) is absent. A {\"rows\":[...]} JSON document as
+produced by the solver page is accepted in place of the rows.
+
+Options:
+  -l, --length N    Equation length (default: length of the first row)
+  -m, --max N       Largest operand value (default: 30)
+      --rules NAME  Rule profile: classic or game (default: classic)
+      --symbols SET Symbols the equation may use, e.g. 0123456789+-*/= (default: all)
+      --json FILE   Read the JSON rows from FILE, or stdin for -
+      --top N       Number of recommended guesses to print (default: 1)
+      --explore     Also consider guesses that cannot be the answer
+      --minimax     Rank guesses by worst-case remaining candidates
+      --dict FILE   Filter a dictionary written by `sumzle dict` instead of searching
+  -h, --help        Print this help
+
+`sumzle dict` enumerates every valid equation of the given length, symbols,
+operand limit and rules once and writes them to FILE as a compact binary
+dictionary.
+
+Exit status is 1 for invalid arguments and 2 when the rows conflict.";
+
+// Shortest equation, e.g. 1=1
+const MIN_LENGTH: usize = 3;
+
+struct Options {
+    length: Option<usize>,
+    max_operand_value: i32,
+    rules: RuleSet,
+    constraints: Constraints,
+    top_n: usize,
+    explore: bool,
+    mode: RecommendMode,
+    dictionary: Option<String>,
+    symbols: CharSet,
+}
+
+struct DictOptions {
+    length: usize,
+    max_operand_value: i32,
+    rules: RuleSet,
+    symbols: CharSet,
+    output: String,
+}
+
+fn parse_symbols(text: &str) -> Result<CharSet, String> {
+    match text.chars().find(|&c| symbol_index(c).is_none()) {
+        Some(c) => Err(format!("Unknown symbol '{}' in --symbols", c)),
+        None => Ok(text.chars().collect()),
+    }
+}
+
+fn parse_dict_args(args: &[String]) -> Result<Option<DictOptions>, String> {
+    let mut length = None;
+    let mut max_operand_value = 30;
+    let mut rules = RuleSet::classic();
+    let mut symbols = CharSet::ALL;
+    let mut output = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().cloned().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-l" | "--length" => {
+                length = Some(value(arg)?.parse().map_err(|_| "--length must be a number".to_string())?);
+            },
+            "-m" | "--max" => {
+                max_operand_value = value(arg)?.parse().map_err(|_| "--max must be a number".to_string())?;
+            },
+            "--rules" => {
+                let name = value(arg)?;
+                rules = RuleSet::from_name(&name).ok_or_else(|| format!("Unknown rule profile '{}'", name))?;
+            },
+            "--symbols" => symbols = parse_symbols(&value(arg)?)?,
+            "-o" | "--output" => output = Some(value(arg)?),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    let length = match length {
+        Some(length @ MIN_LENGTH..=255) => length,
+        Some(_) => return Err(format!("--length must be between {} and 255", MIN_LENGTH)),
+        None => return Err("dict needs --length".to_string()),
+    };
+    let output = output.ok_or_else(|| "dict needs --output".to_string())?;
+    Ok(Some(DictOptions { length, max_operand_value, rules, symbols, output }))
+}
+
+fn write_dictionary(args: &[String]) -> ExitCode {
+    let options = match parse_dict_args(args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(e) => {
+            eprintln!("sumzle: {}\n\n{}", e, USAGE);
+            return ExitCode::from(1);
+        },
+    };
+
+    let mut solver = SumzleSolver::with_symbols(options.length, options.max_operand_value, options.symbols);
+    solver.set_rules(options.rules);
+    let dictionary = Dictionary::generate(&solver);
+    let bytes = dictionary.to_bytes();
+    if let Err(e) = std::fs::write(&options.output, &bytes) {
+        eprintln!("sumzle: {}: {}", options.output, e);
+        return ExitCode::from(1);
+    }
+    println!("Wrote {} equations ({} bytes) to {}", dictionary.len(), bytes.len(), options.output);
+    ExitCode::SUCCESS
+}
+
+fn read_dictionary(path: &str) -> Result<Dictionary, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    Dictionary::from_bytes(&bytes).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let mut length = None;
+    let mut max_operand_value = 30;
+    let mut rules = RuleSet::classic();
+    let mut json = None;
+    let mut rows = Vec::new();
+    let mut top_n = 1;
+    let mut explore = false;
+    let mut mode = RecommendMode::Entropy;
+    let mut dictionary = None;
+    let mut symbols = CharSet::ALL;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().cloned().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-l" | "--length" => {
+                length = Some(value(arg)?.parse().map_err(|_| "--length must be a number".to_string())?);
+            },
+            "-m" | "--max" => {
+                max_operand_value = value(arg)?.parse().map_err(|_| "--max must be a number".to_string())?;
+            },
+            "--rules" => {
+                let name = value(arg)?;
+                rules = RuleSet::from_name(&name).ok_or_else(|| format!("Unknown rule profile '{}'", name))?;
+            },
+            "--json" => {
+                let path = value(arg)?;
+                let text = if path == "-" {
+                    let mut buf = String::new();
+                    std::io::stdin().read_to_string(&mut buf).map_err(|e| e.to_string())?;
+                    buf
+                } else {
+                    std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?
+                };
+                json = Some(text);
+            },
+            "--top" => {
+                top_n = value(arg)?.parse().map_err(|_| "--top must be a number".to_string())?;
+            },
+            "--explore" => explore = true,
+            "--minimax" => mode = RecommendMode::Minimax,
+            "--dict" => dictionary = Some(value(arg)?),
+            "--symbols" => symbols = parse_symbols(&value(arg)?)?,
+            _ if arg.trim_start().starts_with('{') => json = Some(arg.clone()),
+            _ if arg.starts_with('-') && arg.len() > 1 => return Err(format!("Unknown option '{}'", arg)),
+            _ => rows.push(arg.clone()),
+        }
+    }
+
+    let constraints = match json {
+        Some(_) if !rows.is_empty() => return Err("Give either compact rows or JSON, not both".to_string()),
+        Some(text) => Constraints::from_json(&text),
+        None => Constraints::from_compact(&rows),
+    }
+    .map_err(|e| e.to_string())?;
+
+    Ok(Some(Options { length, max_operand_value, rules, constraints, top_n, explore, mode, dictionary, symbols }))
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "dict") {
+        return write_dictionary(&args[1..]);
+    }
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(e) => {
+            eprintln!("sumzle: {}\n\n{}", e, USAGE);
+            return ExitCode::from(1);
+        },
+    };
+
+    let length = match options.length.or_else(|| options.constraints.rows.first().map(|r| r.len())) {
+        Some(length) if length >= MIN_LENGTH => length,
+        Some(_) => {
+            eprintln!("sumzle: the equation length must be at least {}\n\n{}", MIN_LENGTH, USAGE);
+            return ExitCode::from(1);
+        },
+        None => {
+            eprintln!("sumzle: --length is required when no rows are given\n\n{}", USAGE);
+            return ExitCode::from(1);
+        },
+    };
+
+    let mut solver = SumzleSolver::with_symbols(length, options.max_operand_value, options.symbols);
+    solver.set_rules(options.rules);
+    if let Some(path) = &options.dictionary {
+        if let Err(e) = read_dictionary(path).and_then(|dictionary| solver.load_dictionary(dictionary).map_err(|e| format!("{}: {}", path, e))) {
+            eprintln!("sumzle: {}", e);
+            return ExitCode::from(1);
+        }
+    }
+    let results = match solver.search(&options.constraints) {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("sumzle: {}", e);
+            return ExitCode::from(2);
+        },
+    };
+
+    for result in &results {
+        println!("{}", result);
+    }
+    println!("Candidates: {}", results.len());
+
+    let pool = if options.explore && results.len() > 1 {
+        solver.search(&Constraints::default()).unwrap_or_default()
+    } else {
+        results.clone()
+    };
+    for suggestion in rank_guesses(&pool, &results, options.top_n, options.mode) {
+        println!("Recommended: {} ({:.2} bits, worst case {})", suggestion.guess, suggestion.score, suggestion.worst_case);
+    }
+
+    ExitCode::SUCCESS
+}
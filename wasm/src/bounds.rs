@@ -0,0 +1,147 @@
+// Interval bounds on the values an expression of a given width can take
+
+use crate::rules::RuleSet;
+
+// Closed interval of reals; infinite ends stand for "unbounded"
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Interval {
+    pub const UNBOUNDED: Interval = Interval { lo: f64::NEG_INFINITY, hi: f64::INFINITY };
+
+    pub fn new(lo: f64, hi: f64) -> Self {
+        // Arithmetic on infinite ends can produce NaN; give up on the bound then
+        Self {
+            lo: if lo.is_nan() { f64::NEG_INFINITY } else { lo },
+            hi: if hi.is_nan() { f64::INFINITY } else { hi },
+        }
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self::new(self.lo.min(other.lo), self.hi.max(other.hi))
+    }
+
+    pub fn contains_zero(&self) -> bool {
+        self.lo <= 0.0 && self.hi >= 0.0
+    }
+
+    fn from_corners(corners: [f64; 4]) -> Self {
+        if corners.iter().any(|c| c.is_nan()) {
+            return Self::UNBOUNDED;
+        }
+        let lo = corners.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = corners.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Self::new(lo, hi)
+    }
+
+    pub fn sum(self, b: Self) -> Self {
+        Self::new(self.lo + b.lo, self.hi + b.hi)
+    }
+
+    pub fn difference(self, b: Self) -> Self {
+        Self::new(self.lo - b.hi, self.hi - b.lo)
+    }
+
+    pub fn product(self, b: Self) -> Self {
+        Self::from_corners([self.lo * b.lo, self.lo * b.hi, self.hi * b.lo, self.hi * b.hi])
+    }
+
+    pub fn quotient(self, b: Self) -> Self {
+        // A divisor range touching zero admits arbitrarily small divisors
+        if b.contains_zero() {
+            return Self::UNBOUNDED;
+        }
+        Self::from_corners([self.lo / b.lo, self.lo / b.hi, self.hi / b.lo, self.hi / b.hi])
+    }
+
+    pub fn remainder(self, b: Self) -> Self {
+        // |a % b| < |b| and |a % b| <= |a|
+        let m = b.lo.abs().max(b.hi.abs()).min(self.lo.abs().max(self.hi.abs()));
+        Self::new(-m, m)
+    }
+
+    pub fn power(self, b: Self) -> Self {
+        // Only non-negative bases with non-negative exponents are bounded here
+        if self.lo < 0.0 || b.lo < 0.0 {
+            return Self::UNBOUNDED;
+        }
+        Self::new(0.0, self.hi.max(1.0).powf(b.hi))
+    }
+
+    pub fn floor(self) -> Self {
+        // Covers both rounding modes
+        Self::new(self.lo.floor(), self.hi.ceil())
+    }
+
+    // Widen outwards so that rounding in the f64 arithmetic cannot make the
+    // bound tighter than the exact one
+    pub fn widened(self) -> Self {
+        Self::new(self.lo - self.lo.abs() * 1e-9 - 1e-9, self.hi + self.hi.abs() * 1e-9 + 1e-9)
+    }
+}
+
+// `ranges[w]` bounds every value an expression of exactly `w` characters can
+// take, or is None if there is none. The expressions are those the search
+// builds on either side of a relation: numbers up to `max_operand_value`,
+// unary signs, brackets, `!`, `A` and binary operators. Every parse tree is a
+// literal, a sign, a bracket, a postfix operator or a binary operator over
+// shorter expressions, so the union over all of them is a sound
+// over-approximation.
+pub fn expression_ranges(max_width: usize, max_operand_value: i32, rules: &RuleSet) -> Vec<Option<Interval>> {
+    let mut ranges: Vec<Option<Interval>> = vec![None; max_width + 1];
+    let max_operand = max_operand_value as f64;
+    let max_factorial = (2..=rules.max_factorial.clamp(1, 170)).map(|n| n as f64).product::<f64>();
+
+    for w in 1..=max_width {
+        let mut range: Option<Interval> = None;
+        let mut include = |r: Interval| range = Some(range.map_or(r, |acc| acc.union(r)));
+
+        // A number literal of w digits
+        let smallest = if w == 1 { 0.0 } else { 10f64.powi(w as i32 - 1) };
+        let largest = (10f64.powi(w as i32) - 1.0).min(max_operand);
+        if smallest <= largest {
+            include(Interval::new(smallest, largest));
+        }
+
+        if w >= 2 {
+            if let Some(inner) = ranges[w - 1] {
+                // -x and +x
+                include(Interval::new(-inner.hi, -inner.lo));
+                include(inner);
+                // n!, and n! can only be 1..=max_factorial!
+                if inner.hi >= 0.0 {
+                    include(Interval::new(1.0, max_factorial));
+                }
+            }
+        }
+
+        if w >= 3 {
+            if let Some(inner) = ranges[w - 2] {
+                // (x) and [x]
+                include(inner);
+                include(inner.floor());
+            }
+
+            for left in 1..w - 1 {
+                let (Some(a), Some(b)) = (ranges[left], ranges[w - 1 - left]) else {
+                    continue;
+                };
+                include(a.sum(b));
+                include(a.difference(b));
+                include(a.product(b));
+                include(a.quotient(b));
+                include(a.remainder(b));
+                include(a.power(b));
+                // mAn is at most 10!/0! (or 1 for game-rule mA0)
+                include(Interval::new(0.0, 3628800.0));
+            }
+        }
+
+        ranges[w] = range.map(Interval::widened);
+    }
+
+    ranges
+}
@@ -0,0 +1,100 @@
+// Daily puzzle shared by every player: the date and difficulty fix the
+// generator's seed, so no server is needed
+
+use crate::expr::{parse_equation, Equation};
+use crate::generator::PuzzleGenerator;
+use crate::rules::{Difficulty, RuleSet};
+
+// Mixed into every daily seed, so daily puzzles do not coincide with
+// generators seeded by small numbers
+const DAILY_SALT: u64 = 0x5355_4D5A_4C45_0000;
+
+// A calendar date in the proleptic Gregorian calendar
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    // Parse an ISO `YYYY-MM-DD` date
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.split('-');
+        let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() || year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+        let number = |s: &str| if s.bytes().all(|b| b.is_ascii_digit()) { s.parse().ok() } else { None };
+        Self::new(number(year)? as i32, number(month)?, number(day)?)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    // Days since 1970-01-01
+    pub fn days_since_epoch(&self) -> i64 {
+        let year = self.year as i64 - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Seed of the daily generator: the day number and difficulty, salted
+pub fn daily_seed(date: Date, difficulty: Difficulty) -> u64 {
+    let level = match difficulty {
+        Difficulty::Easy => 0,
+        Difficulty::Medium => 1,
+        Difficulty::Hard => 2,
+    };
+    DAILY_SALT ^ ((date.days_since_epoch() as u64) << 2) ^ level
+}
+
+// A daily target as typed into the board and as parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DailyPuzzle {
+    pub text: String,
+    pub equation: Equation,
+}
+
+// The target everyone gets on `date` at `difficulty`. The mapping is part of
+// the game: changing the seed or the generator changes every daily puzzle.
+// Candidates are validated under the classic rules, whose exact arithmetic
+// gives the same answer natively and in wasm; the game's float rules accept
+// everything the generator builds that the classic rules do.
+pub fn daily_puzzle(date: Date, difficulty: Difficulty) -> DailyPuzzle {
+    let mut generator = PuzzleGenerator::new(daily_seed(date, difficulty), difficulty);
+    generator.set_rules(RuleSet::classic());
+    let text = generator.generate().expect("`a+a=2a` fits an unconstrained generator");
+    let equation = parse_equation(&text).expect("generated targets parse");
+    DailyPuzzle { text, equation }
+}
@@ -0,0 +1,214 @@
+// Precomputed dictionaries holding every valid equation of one board, so a
+// search can filter a list instead of exploring the search tree
+//
+// Binary format, little-endian:
+//   magic "SZD3"
+//   length: u8, symbols: u32 (one bit per symbol in `SYMBOLS` order),
+//   max_operand_value: i32
+//   rules: floor rounding u8 (0 floor, 1 truncate), max_factorial i128,
+//          max_abs_value i128, game_operand_rules u8, floor_division_only u8,
+//          js_numbers u8
+//   count: u32
+//   per equation, in sorted order: the number of leading characters shared
+//   with the previous equation (u8), then the remaining characters as ASCII
+
+use std::fmt;
+
+use crate::alphabet::CharSet;
+use crate::rules::{FloorRounding, RuleSet};
+use crate::solver::{Constraints, SumzleSolver};
+
+const MAGIC: &[u8; 4] = b"SZD3";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DictionaryError {
+    BadMagic,
+    InvalidHeader,
+    Truncated,
+    InvalidEntry(usize),
+    TrailingBytes,
+    // The dictionary was built for a different length, operand limit or rule
+    // set, or without some of the solver's symbols
+    Mismatch,
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DictionaryError::BadMagic => write!(f, "Not an equation dictionary"),
+            DictionaryError::InvalidHeader => write!(f, "Dictionary header is invalid"),
+            DictionaryError::Truncated => write!(f, "Dictionary is truncated"),
+            DictionaryError::InvalidEntry(index) => write!(f, "Dictionary entry {} is invalid", index),
+            DictionaryError::TrailingBytes => write!(f, "Dictionary has trailing bytes"),
+            DictionaryError::Mismatch => write!(f, "Dictionary was built for a different length, operand limit, rule set or symbol set"),
+        }
+    }
+}
+
+impl std::error::Error for DictionaryError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dictionary {
+    length: usize,
+    symbols: CharSet,
+    max_operand_value: i32,
+    rules: RuleSet,
+    // Sorted, without duplicates
+    equations: Vec<String>,
+}
+
+impl Dictionary {
+    // Enumerate every equation the solver accepts on an empty board, which
+    // are all it accepts on any board. Lengths above 255 cannot be stored.
+    pub fn generate(solver: &SumzleSolver) -> Self {
+        assert!(solver.length() <= u8::MAX as usize, "dictionary length must fit in a byte");
+        let mut equations = solver.search(&Constraints::default()).unwrap_or_default();
+        equations.sort_unstable();
+        equations.dedup();
+        Self {
+            length: solver.length(),
+            symbols: solver.allowed_symbols(),
+            max_operand_value: solver.max_operand_value(),
+            rules: *solver.rules(),
+            equations,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn symbols(&self) -> CharSet {
+        self.symbols
+    }
+
+    pub fn max_operand_value(&self) -> i32 {
+        self.max_operand_value
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    pub fn equations(&self) -> &[String] {
+        &self.equations
+    }
+
+    pub fn len(&self) -> usize {
+        self.equations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.equations.is_empty()
+    }
+
+    // Whether the dictionary holds every equation this solver searches for.
+    // A dictionary with more symbols than the solver allows still does; the
+    // solver filters out the equations using the others.
+    pub fn matches(&self, solver: &SumzleSolver) -> bool {
+        self.length == solver.length()
+            && self.symbols.is_superset(solver.allowed_symbols())
+            && self.max_operand_value == solver.max_operand_value()
+            && self.rules == *solver.rules()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(self.length as u8);
+        bytes.extend_from_slice(&self.symbols.bits().to_le_bytes());
+        bytes.extend_from_slice(&self.max_operand_value.to_le_bytes());
+        bytes.push(match self.rules.floor_rounding {
+            FloorRounding::Floor => 0,
+            FloorRounding::Truncate => 1,
+        });
+        bytes.extend_from_slice(&self.rules.max_factorial.to_le_bytes());
+        bytes.extend_from_slice(&self.rules.max_abs_value.to_le_bytes());
+        bytes.push(self.rules.game_operand_rules as u8);
+        bytes.push(self.rules.floor_division_only as u8);
+        bytes.push(self.rules.js_numbers as u8);
+        bytes.extend_from_slice(&(self.equations.len() as u32).to_le_bytes());
+
+        let mut previous: &[u8] = &[];
+        for equation in &self.equations {
+            let current = equation.as_bytes();
+            let shared = previous.iter().zip(current).take_while(|(a, b)| a == b).count();
+            bytes.push(shared as u8);
+            bytes.extend_from_slice(&current[shared..]);
+            previous = current;
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DictionaryError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(DictionaryError::BadMagic);
+        }
+        let length = reader.byte()? as usize;
+        let symbols = CharSet::from_bits(u32::from_le_bytes(reader.array()?));
+        let max_operand_value = i32::from_le_bytes(reader.array()?);
+        let floor_rounding = match reader.byte()? {
+            0 => FloorRounding::Floor,
+            1 => FloorRounding::Truncate,
+            _ => return Err(DictionaryError::InvalidHeader),
+        };
+        let rules = RuleSet {
+            floor_rounding,
+            max_factorial: i128::from_le_bytes(reader.array()?),
+            max_abs_value: i128::from_le_bytes(reader.array()?),
+            game_operand_rules: reader.byte()? != 0,
+            floor_division_only: reader.byte()? != 0,
+            js_numbers: reader.byte()? != 0,
+        };
+        let count = u32::from_le_bytes(reader.array()?) as usize;
+
+        let mut equations: Vec<String> = Vec::with_capacity(count.min(bytes.len()));
+        let mut previous = String::new();
+        for index in 0..count {
+            let shared = reader.byte()? as usize;
+            if shared > previous.len() || shared > length {
+                return Err(DictionaryError::InvalidEntry(index));
+            }
+            let suffix = reader.take(length - shared)?;
+            if !suffix.iter().all(|&b| symbols.contains(b as char)) {
+                return Err(DictionaryError::InvalidEntry(index));
+            }
+            let mut equation = previous[..shared].to_string();
+            equation.extend(suffix.iter().map(|&b| b as char));
+            if index > 0 && equation <= previous {
+                return Err(DictionaryError::InvalidEntry(index));
+            }
+            equations.push(equation.clone());
+            previous = equation;
+        }
+        if !reader.bytes.is_empty() {
+            return Err(DictionaryError::TrailingBytes);
+        }
+
+        Ok(Self { length, symbols, max_operand_value, rules, equations })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DictionaryError> {
+        if self.bytes.len() < n {
+            return Err(DictionaryError::Truncated);
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8, DictionaryError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DictionaryError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+}
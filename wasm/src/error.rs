@@ -0,0 +1,122 @@
+// Typed reasons for rejecting an expression or equation
+
+use std::fmt;
+use serde::Serialize;
+
+use crate::expr::ParseError;
+use crate::rational::ArithmeticError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    Empty,
+    InvalidChar(char),
+    LeadingZero,
+    NumberTooLarge,
+    Syntax,
+    UnmatchedBracket,
+    MissingRelation,
+    MultipleRelations,
+    EmptySide,
+    DivisionByZero,
+    Overflow,
+    Irrational,
+    FactorialOutOfRange,
+    InvalidPermutation,
+    NonInteger,
+    // NaN, where the game's JS arithmetic has no value
+    InvalidResult,
+    NotSatisfied,
+}
+
+impl EvalError {
+    // Stable machine-readable identifier, used as `kind` on the JS side
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EvalError::Empty => "empty",
+            EvalError::InvalidChar(_) => "invalid_char",
+            EvalError::LeadingZero => "leading_zero",
+            EvalError::NumberTooLarge => "number_too_large",
+            EvalError::Syntax => "syntax",
+            EvalError::UnmatchedBracket => "unmatched_bracket",
+            EvalError::MissingRelation => "missing_relation",
+            EvalError::MultipleRelations => "multiple_relations",
+            EvalError::EmptySide => "empty_side",
+            EvalError::DivisionByZero => "division_by_zero",
+            EvalError::Overflow => "overflow",
+            EvalError::Irrational => "irrational",
+            EvalError::FactorialOutOfRange => "factorial_out_of_range",
+            EvalError::InvalidPermutation => "invalid_permutation",
+            EvalError::NonInteger => "non_integer",
+            EvalError::InvalidResult => "invalid_result",
+            EvalError::NotSatisfied => "not_satisfied",
+        }
+    }
+
+    // Player-facing message, worded like the game's `isValidEquation`
+    pub fn message(&self) -> String {
+        match self {
+            EvalError::Empty => "表达式不能为空".to_string(),
+            EvalError::InvalidChar(c) => format!("无效字符: {}", c),
+            EvalError::LeadingZero => "数字不能以0开头 (除非是0本身)".to_string(),
+            EvalError::NumberTooLarge => "数字过大".to_string(),
+            EvalError::Syntax => "无效表达式".to_string(),
+            EvalError::UnmatchedBracket => "括号不匹配".to_string(),
+            EvalError::MissingRelation => "必须包含 = 或 >".to_string(),
+            EvalError::MultipleRelations => "只能有一个主操作符".to_string(),
+            EvalError::EmptySide => "操作符两边需有表达式".to_string(),
+            EvalError::DivisionByZero => "除数不能为0".to_string(),
+            EvalError::Overflow => "计算结果超出范围".to_string(),
+            EvalError::Irrational => "乘方结果不是有理数".to_string(),
+            EvalError::FactorialOutOfRange => "阶乘只能用于较小的非负整数".to_string(),
+            EvalError::InvalidPermutation => "排列数mAn需满足0≤n≤m≤10".to_string(),
+            EvalError::NonInteger => "结果必须为整数".to_string(),
+            EvalError::InvalidResult => "表达式计算结果无效".to_string(),
+            EvalError::NotSatisfied => "等式/不等式不成立".to_string(),
+        }
+    }
+
+    pub fn to_info(&self) -> EvalErrorInfo {
+        EvalErrorInfo { kind: self.kind(), message: self.message() }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl From<ParseError> for EvalError {
+    fn from(err: ParseError) -> Self {
+        match err {
+            ParseError::Empty => EvalError::Empty,
+            ParseError::InvalidChar(c) => EvalError::InvalidChar(c),
+            ParseError::LeadingZero => EvalError::LeadingZero,
+            ParseError::NumberTooLarge => EvalError::NumberTooLarge,
+            ParseError::UnexpectedToken(_) | ParseError::UnexpectedEnd => EvalError::Syntax,
+            ParseError::UnmatchedBracket => EvalError::UnmatchedBracket,
+            ParseError::MissingRelation => EvalError::MissingRelation,
+            ParseError::MultipleRelations => EvalError::MultipleRelations,
+            ParseError::EmptySide => EvalError::EmptySide,
+        }
+    }
+}
+
+impl From<ArithmeticError> for EvalError {
+    fn from(err: ArithmeticError) -> Self {
+        match err {
+            ArithmeticError::Overflow => EvalError::Overflow,
+            ArithmeticError::DivisionByZero => EvalError::DivisionByZero,
+            ArithmeticError::Irrational => EvalError::Irrational,
+        }
+    }
+}
+
+// Structured error handed to JS: `{ kind, message }`
+#[derive(Serialize, Debug)]
+pub struct EvalErrorInfo {
+    pub kind: &'static str,
+    pub message: String,
+}
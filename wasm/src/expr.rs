@@ -0,0 +1,289 @@
+// Tokenizer, AST and Pratt parser for Sumzle expressions
+
+// Lexical tokens of a Sumzle expression
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Num(i64),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    Perm,
+    Bang,
+    LParen,
+    RParen,
+    LFloor,
+    RFloor,
+    Eq,
+    Gt,
+}
+
+// Binary arithmetic operators (permutation has its own AST node)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+}
+
+// Typed syntax tree of one side of an equation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Num(i64),
+    Neg(Box<Expr>),
+    BinOp(BinOp, Box<Expr>, Box<Expr>),
+    Factorial(Box<Expr>),
+    Perm(Box<Expr>, Box<Expr>),
+    Floor(Box<Expr>),
+    Paren(Box<Expr>),
+}
+
+// Main operator of an equation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Eq,
+    Gt,
+}
+
+// A parsed `lhs = rhs` or `lhs > rhs` equation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Equation {
+    pub lhs: Expr,
+    pub relation: Relation,
+    pub rhs: Expr,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidChar(char),
+    LeadingZero,
+    NumberTooLarge,
+    UnexpectedToken(Token),
+    UnexpectedEnd,
+    UnmatchedBracket,
+    MissingRelation,
+    MultipleRelations,
+    EmptySide,
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::with_capacity(chars.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            // Numbers other than 0 itself cannot start with 0
+            if c == '0' && i - start > 1 {
+                return Err(ParseError::LeadingZero);
+            }
+            let mut value: i64 = 0;
+            for d in &chars[start..i] {
+                value = value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(d.to_digit(10).unwrap() as i64))
+                    .ok_or(ParseError::NumberTooLarge)?;
+            }
+            tokens.push(Token::Num(value));
+            continue;
+        }
+
+        tokens.push(match c {
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '^' => Token::Caret,
+            'A' => Token::Perm,
+            '!' => Token::Bang,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LFloor,
+            ']' => Token::RFloor,
+            '=' => Token::Eq,
+            '>' => Token::Gt,
+            _ => return Err(ParseError::InvalidChar(c)),
+        });
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+// Grammar variations between rule sets
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Grammar {
+    // Apply `A` before a following `!`, so 3A2! is (3A2)! rather than 3A(2!)
+    pub permutation_before_factorial: bool,
+}
+
+impl Grammar {
+    pub(crate) fn bang_binding_power(&self) -> u8 {
+        if self.permutation_before_factorial { POSTFIX_BANG_AFTER_PERM_BP } else { POSTFIX_BANG_BP }
+    }
+}
+
+// Binding powers, loosest first: `+ -`, `* / %`, unary minus, `^` (right
+// associative), `A`, postfix `!`
+pub(crate) const PREFIX_NEG_BP: u8 = 5;
+const POSTFIX_BANG_BP: u8 = 11;
+const POSTFIX_BANG_AFTER_PERM_BP: u8 = 9;
+
+pub(crate) fn infix_binding_power(token: Token) -> Option<(u8, u8)> {
+    match token {
+        Token::Plus | Token::Minus => Some((1, 2)),
+        Token::Star | Token::Slash | Token::Percent => Some((3, 4)),
+        Token::Caret => Some((8, 7)),
+        Token::Perm => Some((9, 10)),
+        _ => None,
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    bang_bp: u8,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn expect_closing(&mut self, closing: Token) -> Result<(), ParseError> {
+        match self.next() {
+            Some(t) if t == closing => Ok(()),
+            Some(Token::RParen) | Some(Token::RFloor) | None => Err(ParseError::UnmatchedBracket),
+            Some(t) => Err(ParseError::UnexpectedToken(t)),
+        }
+    }
+
+    fn parse_expr(&mut self, min_bp: u8) -> Result<Expr, ParseError> {
+        let mut lhs = match self.next() {
+            Some(Token::Num(n)) => Expr::Num(n),
+            Some(Token::Minus) => Expr::Neg(Box::new(self.parse_expr(PREFIX_NEG_BP)?)),
+            Some(Token::Plus) => self.parse_expr(PREFIX_NEG_BP)?,
+            Some(Token::LParen) => {
+                let inner = self.parse_expr(0)?;
+                self.expect_closing(Token::RParen)?;
+                Expr::Paren(Box::new(inner))
+            }
+            Some(Token::LFloor) => {
+                let inner = self.parse_expr(0)?;
+                self.expect_closing(Token::RFloor)?;
+                Expr::Floor(Box::new(inner))
+            }
+            Some(Token::RParen) | Some(Token::RFloor) => return Err(ParseError::UnmatchedBracket),
+            Some(t) => return Err(ParseError::UnexpectedToken(t)),
+            None => return Err(ParseError::UnexpectedEnd),
+        };
+
+        while let Some(op) = self.peek() {
+            if op == Token::Bang {
+                if self.bang_bp < min_bp {
+                    break;
+                }
+                self.pos += 1;
+                lhs = Expr::Factorial(Box::new(lhs));
+                continue;
+            }
+
+            let Some((l_bp, r_bp)) = infix_binding_power(op) else { break };
+            if l_bp < min_bp {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.parse_expr(r_bp)?;
+
+            lhs = match op {
+                Token::Perm => Expr::Perm(Box::new(lhs), Box::new(rhs)),
+                Token::Plus => Expr::BinOp(BinOp::Add, Box::new(lhs), Box::new(rhs)),
+                Token::Minus => Expr::BinOp(BinOp::Sub, Box::new(lhs), Box::new(rhs)),
+                Token::Star => Expr::BinOp(BinOp::Mul, Box::new(lhs), Box::new(rhs)),
+                Token::Slash => Expr::BinOp(BinOp::Div, Box::new(lhs), Box::new(rhs)),
+                Token::Percent => Expr::BinOp(BinOp::Mod, Box::new(lhs), Box::new(rhs)),
+                Token::Caret => Expr::BinOp(BinOp::Pow, Box::new(lhs), Box::new(rhs)),
+                _ => unreachable!(),
+            };
+        }
+
+        Ok(lhs)
+    }
+}
+
+fn parse_tokens(tokens: &[Token], grammar: Grammar) -> Result<Expr, ParseError> {
+    if tokens.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let mut parser = Parser { tokens, pos: 0, bang_bp: grammar.bang_binding_power() };
+    let expr = parser.parse_expr(0)?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(Token::RParen) | Some(Token::RFloor) => Err(ParseError::UnmatchedBracket),
+        Some(t) => Err(ParseError::UnexpectedToken(t)),
+    }
+}
+
+// Parse one side of an equation
+pub fn parse_expression(input: &str) -> Result<Expr, ParseError> {
+    parse_expression_with(input, Grammar::default())
+}
+
+pub fn parse_expression_with(input: &str, grammar: Grammar) -> Result<Expr, ParseError> {
+    parse_tokens(&tokenize(input)?, grammar)
+}
+
+// Parse a full equation with exactly one top-level `=` or `>`
+pub fn parse_equation(input: &str) -> Result<Equation, ParseError> {
+    parse_equation_with(input, Grammar::default())
+}
+
+pub fn parse_equation_with(input: &str, grammar: Grammar) -> Result<Equation, ParseError> {
+    let tokens = tokenize(input)?;
+
+    let mut split = None;
+    let mut depth = 0i32;
+    for (i, &t) in tokens.iter().enumerate() {
+        match t {
+            Token::LParen | Token::LFloor => depth += 1,
+            Token::RParen | Token::RFloor => depth -= 1,
+            Token::Eq | Token::Gt if depth == 0 => {
+                if split.is_some() {
+                    return Err(ParseError::MultipleRelations);
+                }
+                let relation = if t == Token::Eq { Relation::Eq } else { Relation::Gt };
+                split = Some((i, relation));
+            }
+            _ => {}
+        }
+    }
+
+    let (index, relation) = split.ok_or(ParseError::MissingRelation)?;
+    if index == 0 || index == tokens.len() - 1 {
+        return Err(ParseError::EmptySide);
+    }
+    Ok(Equation {
+        lhs: parse_tokens(&tokens[..index], grammar)?,
+        relation,
+        rhs: parse_tokens(&tokens[index + 1..], grammar)?,
+    })
+}
@@ -0,0 +1,84 @@
+// Wordle-style feedback scoring, a port of `checkGuess` in Sumzle.html
+
+use serde::{Deserialize, Serialize};
+
+use crate::solver::{Constraints, Row, Tile};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TileState {
+    Correct,
+    Present,
+    Absent,
+}
+
+impl TileState {
+    // Tile state name used in the solver's constraint JSON
+    pub fn as_constraint_str(&self) -> &'static str {
+        match self {
+            TileState::Correct => "correct",
+            TileState::Present => "present",
+            TileState::Absent => "empty",
+        }
+    }
+
+    pub fn from_constraint_str(state: &str) -> Option<Self> {
+        match state {
+            "correct" => Some(TileState::Correct),
+            "present" => Some(TileState::Present),
+            "empty" | "absent" => Some(TileState::Absent),
+            _ => None,
+        }
+    }
+}
+
+// Score a guess against the target. Greens are assigned first and consume
+// their target character; each remaining guess character then takes the
+// leftmost unused matching target character, if any, as a yellow. Positions
+// beyond the end of the target are always absent.
+pub fn score(guess: &str, target: &str) -> Vec<TileState> {
+    let guess: Vec<char> = guess.chars().collect();
+    let mut remaining: Vec<Option<char>> = target.chars().map(Some).collect();
+    let target_len = remaining.len();
+    let mut states = vec![TileState::Absent; guess.len()];
+
+    for (i, &c) in guess.iter().enumerate() {
+        if i < target_len && remaining[i] == Some(c) {
+            states[i] = TileState::Correct;
+            remaining[i] = None;
+        }
+    }
+
+    for (i, &c) in guess.iter().enumerate() {
+        if i >= target_len || states[i] == TileState::Correct {
+            continue;
+        }
+        if let Some(j) = remaining.iter().position(|&t| t == Some(c)) {
+            states[i] = TileState::Present;
+            remaining[j] = None;
+        }
+    }
+
+    states
+}
+
+// Build the constraint row for a scored guess
+pub fn to_row(guess: &str, states: &[TileState]) -> Row {
+    guess
+        .chars()
+        .zip(states)
+        .map(|(c, state)| Tile { char: c.to_string(), state: state.as_constraint_str().to_string() })
+        .collect()
+}
+
+// Read the states back out of a constraint row
+pub fn row_states(row: &Row) -> Option<Vec<TileState>> {
+    row.iter().map(|tile| TileState::from_constraint_str(&tile.state)).collect()
+}
+
+// Constraints produced by playing the given guesses against a target
+pub fn constraints_for(guesses: &[&str], target: &str) -> Constraints {
+    Constraints {
+        rows: guesses.iter().map(|guess| to_row(guess, &score(guess, target))).collect(),
+    }
+}
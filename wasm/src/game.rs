@@ -0,0 +1,312 @@
+// One game of Sumzle: the rules the DOM code used to enforce (rows per
+// difficulty, win and loss, hints, one-try mode and sudden death), kept here
+// so the page only renders the session
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::error::{EvalError, EvalErrorInfo};
+use crate::feedback::{score, TileState};
+use crate::rng::SplitMix64;
+use crate::rules::{Difficulty, RuleSet};
+use crate::solver::SumzleSolver;
+
+// Time limit of the game's sudden death mode
+pub const SUDDEN_DEATH_SECONDS: u32 = 30;
+
+// Chance that a hint reveals a position rather than a symbol, as in the game
+const POSITION_HINT_CHANCE: f64 = 0.65;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GameRules {
+    pub difficulty: Difficulty,
+    // A single row whatever the difficulty
+    pub one_try: bool,
+    // Seconds from the start after which the game is lost
+    pub sudden_death_seconds: Option<u32>,
+}
+
+impl GameRules {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self { difficulty, ..Self::default() }
+    }
+
+    pub fn rows(&self) -> usize {
+        if self.one_try { 1 } else { self.difficulty.attempts() }
+    }
+}
+
+// Named like the statuses the game records in its history
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GameStatus {
+    InProgress,
+    Won,
+    Lost,
+    LostSuddenDeath,
+    Abandoned,
+}
+
+impl GameStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameStatus::InProgress => "IN_PROGRESS",
+            GameStatus::Won => "WON",
+            GameStatus::Lost => "LOST",
+            GameStatus::LostSuddenDeath => "LOST_SUDDEN_DEATH",
+            GameStatus::Abandoned => "ABANDONED",
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        *self != GameStatus::InProgress
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuessError {
+    GameOver,
+    WrongLength { expected: usize, found: usize },
+    // The guess changes a position locked by a hint
+    HintLocked(usize),
+    Invalid(EvalError),
+}
+
+impl GuessError {
+    // Stable machine-readable identifier, used as `kind` on the JS side
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GuessError::GameOver => "game_over",
+            GuessError::WrongLength { .. } => "wrong_length",
+            GuessError::HintLocked(_) => "hint_locked",
+            GuessError::Invalid(e) => e.kind(),
+        }
+    }
+
+    // Player-facing message, worded like the game's
+    pub fn message(&self) -> String {
+        match self {
+            GuessError::GameOver => "游戏已结束".to_string(),
+            GuessError::WrongLength { .. } => "请填满当前行".to_string(),
+            GuessError::HintLocked(index) => format!("第{}位已由提示锁定", index + 1),
+            GuessError::Invalid(e) => e.message(),
+        }
+    }
+
+    pub fn to_info(&self) -> EvalErrorInfo {
+        EvalErrorInfo { kind: self.kind(), message: self.message() }
+    }
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl std::error::Error for GuessError {}
+
+impl From<EvalError> for GuessError {
+    fn from(e: EvalError) -> Self {
+        GuessError::Invalid(e)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Guess {
+    pub equation: String,
+    pub feedback: Vec<TileState>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Hint {
+    // The target's character at `index`, locked into the current row
+    Position { index: usize, char: char },
+    // A character of the target, shown as present on the keyboard
+    Symbol { char: char },
+}
+
+pub struct GameSession {
+    target: Vec<char>,
+    rules: GameRules,
+    solver: SumzleSolver,
+    guesses: Vec<Guess>,
+    // Characters hints fixed in the row being entered
+    locked: Vec<Option<char>>,
+    // Keyboard colours given by hints, which outlast the row: correct for a
+    // locked character, present for a revealed symbol
+    hinted_keys: Vec<(char, TileState)>,
+    hints_used: usize,
+    status: GameStatus,
+    rng: SplitMix64,
+}
+
+impl GameSession {
+    // Guesses are checked under the game's rules
+    pub fn new(target: &str, rules: GameRules) -> Self {
+        Self::with_rule_set(target, rules, RuleSet::game_compatible())
+    }
+
+    pub fn with_rule_set(target: &str, rules: GameRules, rule_set: RuleSet) -> Self {
+        let target: Vec<char> = target.chars().collect();
+        Self {
+            solver: SumzleSolver::with_rules(target.len(), 0, rule_set),
+            locked: vec![None; target.len()],
+            target,
+            rules,
+            guesses: Vec::new(),
+            hinted_keys: Vec::new(),
+            hints_used: 0,
+            status: GameStatus::InProgress,
+            rng: SplitMix64::new(0),
+        }
+    }
+
+    // Seed the choice of hints; the same seed gives the same hints
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = SplitMix64::new(seed);
+    }
+
+    pub fn target(&self) -> String {
+        self.target.iter().collect()
+    }
+
+    pub fn length(&self) -> usize {
+        self.target.len()
+    }
+
+    pub fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rules.rows()
+    }
+
+    pub fn guesses(&self) -> &[Guess] {
+        &self.guesses
+    }
+
+    pub fn remaining_rows(&self) -> usize {
+        self.rows().saturating_sub(self.guesses.len())
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.status.is_over()
+    }
+
+    pub fn hints_used(&self) -> usize {
+        self.hints_used
+    }
+
+    // The character a hint locked at `index` of the current row, if any
+    pub fn locked(&self, index: usize) -> Option<char> {
+        self.locked.get(index).copied().flatten()
+    }
+
+    // Submit a guess for the current row. Invalid guesses leave the session
+    // unchanged, like the game's shaking row; valid ones use up the row.
+    pub fn submit(&mut self, guess: &str) -> Result<Vec<TileState>, GuessError> {
+        if self.is_over() {
+            return Err(GuessError::GameOver);
+        }
+        let chars: Vec<char> = guess.chars().collect();
+        if chars.len() != self.target.len() {
+            return Err(GuessError::WrongLength { expected: self.target.len(), found: chars.len() });
+        }
+        if let Some(index) = (0..chars.len()).find(|&i| self.locked[i].is_some_and(|c| c != chars[i])) {
+            return Err(GuessError::HintLocked(index));
+        }
+        self.solver.validate_equation(guess)?;
+
+        let feedback = score(guess, &self.target());
+        self.guesses.push(Guess { equation: guess.to_string(), feedback: feedback.clone() });
+        self.locked.iter_mut().for_each(|locked| *locked = None);
+        if chars == self.target {
+            self.status = GameStatus::Won;
+        } else if self.guesses.len() >= self.rows() {
+            self.status = GameStatus::Lost;
+        }
+        Ok(feedback)
+    }
+
+    // Report the time since the game started; in sudden death mode the game
+    // is lost once the limit is reached
+    pub fn tick(&mut self, elapsed_seconds: u32) -> GameStatus {
+        if !self.is_over() && self.rules.sudden_death_seconds.is_some_and(|limit| elapsed_seconds >= limit) {
+            self.status = GameStatus::LostSuddenDeath;
+        }
+        self.status
+    }
+
+    pub fn abandon(&mut self) {
+        if !self.is_over() {
+            self.status = GameStatus::Abandoned;
+        }
+    }
+
+    // The best state each guess or hint has shown for `c`, as the keyboard
+    // colours it
+    pub fn key_state(&self, c: char) -> Option<TileState> {
+        let mut state = None;
+        let shown = self.guesses.iter().flat_map(|guess| guess.equation.chars().zip(guess.feedback.iter().copied()));
+        for tile in shown.chain(self.hinted_keys.iter().copied()).filter_map(|(key, tile)| (key == c).then_some(tile)) {
+            state = Some(match (state, tile) {
+                (Some(TileState::Correct), _) | (_, TileState::Correct) => TileState::Correct,
+                (Some(TileState::Present), _) | (_, TileState::Present) => TileState::Present,
+                _ => TileState::Absent,
+            });
+        }
+        state
+    }
+
+    // Reveal something about the target, as the game's hint button does,
+    // given the current row as typed so far: a position that is neither
+    // locked nor typed correctly (locked in), or a symbol of the target the
+    // keyboard shows as neither correct nor present. None if the game is
+    // over or nothing is left to reveal; only hints given count towards
+    // `hints_used`.
+    pub fn hint(&mut self, input: &str) -> Option<Hint> {
+        if self.is_over() {
+            return None;
+        }
+        // The game's `isCompletingHint`: a position is only revealed while
+        // another one is still wrong, so a hint never completes the row
+        let input: Vec<char> = input.chars().collect();
+        let wrong: Vec<usize> = (0..self.target.len())
+            .filter(|&i| self.locked[i].is_none() && input.get(i) != Some(&self.target[i]))
+            .collect();
+        let positions: Vec<usize> = if wrong.len() > 1 { wrong } else { Vec::new() };
+        // In the order the game's `Object.keys` lists the target's
+        // characters: digits ascending, then the rest as they first appear
+        let mut symbols: Vec<char> = Vec::new();
+        for &c in &self.target {
+            if !symbols.contains(&c) && !matches!(self.key_state(c), Some(TileState::Correct | TileState::Present)) {
+                symbols.push(c);
+            }
+        }
+        symbols.sort_by_key(|c| c.to_digit(10).unwrap_or(10));
+
+        let wants_position = self.rng.next_f64() < POSITION_HINT_CHANCE;
+        let hint = if (wants_position || symbols.is_empty()) && !positions.is_empty() {
+            let index = *self.rng.pick(&positions);
+            self.locked[index] = Some(self.target[index]);
+            self.hinted_keys.push((self.target[index], TileState::Correct));
+            Hint::Position { index, char: self.target[index] }
+        } else if !symbols.is_empty() {
+            let c = *self.rng.pick(&symbols);
+            self.hinted_keys.push((c, TileState::Present));
+            Hint::Symbol { char: c }
+        } else {
+            return None;
+        };
+        self.hints_used += 1;
+        Some(hint)
+    }
+}
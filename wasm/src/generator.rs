@@ -0,0 +1,347 @@
+// Target equation generator, ported from the game's `generateRandomEquation`
+// with a seedable random number generator so puzzles are reproducible
+
+use crate::alphabet::CharSet;
+use crate::grade::{PuzzleGrade, PuzzleGrader};
+use crate::rng::SplitMix64;
+use crate::rules::{Difficulty, RuleSet};
+use crate::solver::{Constraints, SumzleSolver};
+
+// Bounds the game puts on generated equations
+const MIN_EQUATION_LENGTH: usize = 3;
+const DEFAULT_MAX_EQUATION_LENGTH: usize = 15;
+
+// Operand limit and per-length placement budget of the search `generate`
+// falls back to when `a+a=2a` does not fit the constraints
+const FALLBACK_MAX_OPERAND: i32 = 30;
+const FALLBACK_SEARCH_BUDGET: usize = 20_000;
+
+// The game's `segmentGenerators`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Segment {
+    Number,
+    ParenthesizedBinaryOp,
+    Factorial,
+    FloorDiv,
+    Permutation,
+}
+
+const SEGMENTS: [Segment; 5] = [
+    Segment::Number,
+    Segment::ParenthesizedBinaryOp,
+    Segment::Factorial,
+    Segment::FloorDiv,
+    Segment::Permutation,
+];
+
+pub struct PuzzleGenerator {
+    rng: SplitMix64,
+    difficulty: Difficulty,
+    // Exact length to generate, or any length the game allows
+    length: Option<usize>,
+    symbols: CharSet,
+    max_attempts: usize,
+    solver: SumzleSolver,
+}
+
+impl PuzzleGenerator {
+    pub fn new(seed: u64, difficulty: Difficulty) -> Self {
+        Self {
+            rng: SplitMix64::new(seed),
+            difficulty,
+            length: None,
+            symbols: CharSet::ALL,
+            max_attempts: 1000,
+            solver: SumzleSolver::with_rules(0, 0, RuleSet::game_compatible()),
+        }
+    }
+
+    // Only generate equations of exactly `length` characters
+    pub fn set_length(&mut self, length: Option<usize>) {
+        self.length = length;
+    }
+
+    // Only generate equations using these symbols, for custom levels and easy modes
+    pub fn set_symbols(&mut self, symbols: CharSet) {
+        self.symbols = symbols;
+    }
+
+    // Rules the generated equations must be valid under; the game's by default
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.solver.set_rules(rules);
+    }
+
+    pub fn set_max_attempts(&mut self, max_attempts: usize) {
+        self.max_attempts = max_attempts;
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    // A random valid equation, or None if none was found within the attempt limit
+    pub fn try_generate(&mut self) -> Option<String> {
+        (0..self.max_attempts).find_map(|_| self.attempt())
+    }
+
+    // A random valid equation within the length and symbols. When the
+    // attempt limit is reached it falls back to `a+a=2a` like the game does,
+    // or, if that does not fit, to one of the first equations a search of the
+    // allowed lengths finds. None if that search finds nothing either.
+    pub fn generate(&mut self) -> Option<String> {
+        self.try_generate().or_else(|| self.fallback())
+    }
+
+    // Like `try_generate`, but only accept targets the grader's solver finds
+    // within the difficulty's attempts (8, 6 or 4). The grader should use the
+    // generator's rules and symbols.
+    pub fn try_generate_graded(&mut self, grader: &mut PuzzleGrader) -> Option<(String, PuzzleGrade)> {
+        for _ in 0..self.max_attempts {
+            let Some(target) = self.attempt() else {
+                continue;
+            };
+            let grade = grader.grade(&target);
+            if grade.fits(self.difficulty) {
+                return Some((target, grade));
+            }
+        }
+        None
+    }
+
+    fn max_length(&self) -> usize {
+        self.length.map_or(DEFAULT_MAX_EQUATION_LENGTH, |length| length.max(DEFAULT_MAX_EQUATION_LENGTH))
+    }
+
+    fn allows(&self, text: &str) -> bool {
+        text.chars().all(|c| self.symbols.contains(c))
+    }
+
+    fn fits(&self, equation: &str) -> bool {
+        let length_ok = match self.length {
+            Some(length) => equation.len() == length,
+            None => (MIN_EQUATION_LENGTH..=self.max_length()).contains(&equation.len()),
+        };
+        length_ok && self.allows(equation) && self.solver.is_valid_solution(equation)
+    }
+
+    fn fallback(&mut self) -> Option<String> {
+        let a = self.rng.random_int(1, 9);
+        let doubled = format!("{}+{}={}", a, a, a + a);
+        if self.fits(&doubled) {
+            return Some(doubled);
+        }
+        if !self.symbols.contains('=') && !self.symbols.contains('>') {
+            return None;
+        }
+        let lengths = match self.length {
+            Some(length) => length..=length,
+            None => MIN_EQUATION_LENGTH..=DEFAULT_MAX_EQUATION_LENGTH,
+        };
+        for length in lengths {
+            let mut solver = SumzleSolver::with_symbols(length, FALLBACK_MAX_OPERAND, self.symbols);
+            solver.set_rules(*self.solver.rules());
+            let Ok(mut session) = solver.start_search(&Constraints::default()) else {
+                continue;
+            };
+            let found = session.step(FALLBACK_SEARCH_BUDGET).new_results;
+            if !found.is_empty() {
+                return Some(self.rng.pick(&found).clone());
+            }
+        }
+        None
+    }
+
+    fn evaluate(&self, expr: &str) -> Option<i64> {
+        self.solver.try_evaluate_expression(expr).ok().map(i64::from)
+    }
+
+    // One pass of the game's generation loop
+    fn attempt(&mut self) -> Option<String> {
+        let max_length = self.max_length();
+
+        let wants_gt = self.rng.next_f64() >= 0.85;
+        let main_operator = match (self.symbols.contains('='), self.symbols.contains('>')) {
+            (true, true) if self.difficulty == Difficulty::Hard => '=',
+            (true, true) => if wants_gt { '>' } else { '=' },
+            (true, false) => '=',
+            (false, true) => '>',
+            (false, false) => return None,
+        };
+
+        let (lhs, rhs) = if main_operator == '=' {
+            let lhs = self.complex_lhs();
+            let value = self.evaluate(&lhs)?;
+            (lhs, value.to_string())
+        } else {
+            let mut lhs = self.complex_lhs();
+            let mut rhs = self.complex_lhs();
+            let left = self.evaluate(&lhs)?;
+            let right = self.evaluate(&rhs)?;
+            if left <= right {
+                if right > left && rhs.len() + 1 + lhs.len() <= max_length {
+                    std::mem::swap(&mut lhs, &mut rhs);
+                } else {
+                    let adjustment = self.rng.random_int(1, 5);
+                    let raised = (left + adjustment).to_string();
+                    let lowered = (right - adjustment).to_string();
+                    if self.rng.next_f64() < 0.5 && raised.len() + 1 + rhs.len() <= max_length {
+                        lhs = raised;
+                    } else if lhs.len() + 1 + lowered.len() <= max_length && right - adjustment >= 0 {
+                        rhs = lowered;
+                        if left <= right - adjustment {
+                            return None;
+                        }
+                    } else {
+                        return None;
+                    }
+                }
+            }
+            (lhs, rhs)
+        };
+
+        let equation = format!("{}{}{}", lhs, main_operator, rhs);
+        self.fits(&equation).then_some(equation)
+    }
+
+    // The game's `generateComplexLHS`: a segment, then further segments joined
+    // with `+ - * %` while there is room and a 3 in 4 chance holds
+    fn complex_lhs(&mut self) -> String {
+        let max_length = self.max_length();
+        let mut expr = strip_outer_parentheses(self.single_segment());
+        let mut margin = 5;
+
+        while expr.len() + margin < max_length && self.rng.next_f64() < 0.75 {
+            let next = self.single_segment();
+            if expr.len() + next.len() + 1 + margin > max_length {
+                break;
+            }
+
+            let op = *self.rng.pick(&['+', '-', '*', '%']);
+            let expr_part = wrap_for(&expr, op);
+            let next_part = wrap_for(&next, op);
+            let combined = if self.rng.next_f64() < 0.5 {
+                format!("{}{}{}", expr_part, op, next_part)
+            } else {
+                format!("{}{}{}", next_part, op, expr_part)
+            };
+            // The game evaluates a 10-character prefix here; the whole
+            // expression is what has to evaluate
+            if !self.allows(&combined) || self.evaluate(&combined).is_none() {
+                break;
+            }
+            expr = combined;
+
+            if expr.len() > 30 {
+                margin = 7;
+            }
+            if expr.len() > 50 {
+                margin = 9;
+            }
+        }
+
+        if self.evaluate(&expr).is_none() {
+            return self.rng.random_int(1, 30).to_string();
+        }
+        expr
+    }
+
+    // The game's `generateSingleExpressionSegment`, choosing only among the
+    // segments the symbol set can express
+    fn single_segment(&mut self) -> String {
+        let available: Vec<Segment> = SEGMENTS.iter().copied().filter(|&segment| self.segment_available(segment)).collect();
+        match *self.rng.pick(&available) {
+            Segment::Number => self.rng.random_int(1, 20).to_string(),
+            Segment::ParenthesizedBinaryOp => self.parenthesized_binary_op(),
+            Segment::Factorial => format!("{}!", self.rng.random_int(1, 5)),
+            Segment::FloorDiv => format!("[{}/{}]", self.rng.random_int(1, 10), self.rng.random_int(2, 5)),
+            Segment::Permutation => {
+                let m = self.rng.random_int(2, 7);
+                let n = self.rng.random_int(1, m);
+                format!("{}A{}", m, n)
+            },
+        }
+    }
+
+    fn segment_available(&self, segment: Segment) -> bool {
+        match segment {
+            Segment::Number => true,
+            Segment::ParenthesizedBinaryOp => !self.binary_ops().is_empty(),
+            Segment::Factorial => self.allows("!"),
+            Segment::FloorDiv => self.allows("[/]"),
+            Segment::Permutation => self.allows("A"),
+        }
+    }
+
+    // Operators of `generateParenthesizedBinaryOpSegment`, with `^` weighted
+    // four times; `* ^ %` are always bracketed
+    fn binary_ops(&self) -> Vec<char> {
+        ['+', '-', '*', '^', '^', '^', '^', '%']
+            .into_iter()
+            .filter(|&op| self.symbols.contains(op) && (matches!(op, '+' | '-') || self.allows("()")))
+            .collect()
+    }
+
+    fn parenthesized_binary_op(&mut self) -> String {
+        let a = self.rng.random_int(1, 10);
+        let b = self.rng.random_int(1, 10);
+        let op = *self.rng.pick(&self.binary_ops());
+        let parentheses = self.allows("()");
+
+        if op == '^' {
+            // A small power, or a square root written as n^(1/2)
+            if self.rng.next_f64() < 0.6 || !self.allows("/") {
+                return format!("({}^{})", a, self.rng.random_int(2, 3));
+            }
+            let base = self.rng.random_int(2, 5);
+            return format!("({}^(1/2))", base * base);
+        }
+        if op == '-' && a < b {
+            return if parentheses { format!("({}-{})", b, a) } else { format!("{}-{}", b, a) };
+        }
+        if op == '*' || op == '%' {
+            format!("({}{}{})", a, op, b)
+        } else {
+            format!("{}{}{}", a, op, b)
+        }
+    }
+}
+
+// The game's `checkBrackets`: every bracket closes the innermost open one
+fn brackets_balanced(text: &str) -> bool {
+    let mut open = Vec::new();
+    for c in text.chars() {
+        match c {
+            '(' | '[' => open.push(c),
+            ')' if open.pop() != Some('(') => return false,
+            ']' if open.pop() != Some('[') => return false,
+            _ => {},
+        }
+    }
+    open.is_empty()
+}
+
+fn strip_outer_parentheses(segment: String) -> String {
+    if segment.len() >= 2 && segment.starts_with('(') && segment.ends_with(')') && brackets_balanced(&segment[1..segment.len() - 1]) {
+        segment[1..segment.len() - 1].to_string()
+    } else {
+        segment
+    }
+}
+
+// A number, `n!` or `mAn` on its own
+fn is_simple_segment(segment: &str) -> bool {
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    digits(segment)
+        || segment.strip_suffix('!').is_some_and(digits)
+        || segment.split_once('A').is_some_and(|(m, n)| digits(m) && digits(n))
+}
+
+// The game's `shouldWrap`: bracket a compound operand of `* / % ^` unless it
+// is already enclosed
+fn wrap_for(segment: &str, op: char) -> String {
+    let inner = |open: char, close: char| {
+        segment.len() >= 2 && segment.starts_with(open) && segment.ends_with(close) && brackets_balanced(&segment[1..segment.len() - 1])
+    };
+    let wrap = !is_simple_segment(segment) && !inner('(', ')') && !inner('[', ']') && matches!(op, '*' | '/' | '%' | '^');
+    if wrap { format!("({})", segment) } else { segment.to_string() }
+}
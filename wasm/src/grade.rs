@@ -0,0 +1,308 @@
+// Grading targets by how many guesses a solver needs to find them
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::alphabet::CharSet;
+use crate::dictionary::{Dictionary, DictionaryError};
+use crate::feedback::{score, to_row};
+use crate::generator::PuzzleGenerator;
+use crate::recommend::{rank_guesses, RecommendMode};
+use crate::rng::SplitMix64;
+use crate::rules::{Difficulty, RuleSet};
+use crate::solver::{Constraints, SumzleSolver};
+
+// Guesses and candidates the entropy solver scores per turn; larger pools are
+// ranked on an evenly spaced sample to keep a turn to a fraction of a second
+const SAMPLE_GUESSES: usize = 200;
+const SAMPLE_CANDIDATES: usize = 2000;
+
+// Games are abandoned after this many guesses
+const MAX_SIMULATED_GUESSES: usize = 20;
+
+// Lengths too long to search are played on samples: this many generated
+// targets of the length for the first guess, then the candidates a search
+// under the feedback so far finds in steps of this many placements, for this
+// many steps or, while it has found none, up to the limit
+const SAMPLED_TARGETS: usize = 500;
+const SAMPLED_SEARCH_STEP: usize = 100_000;
+const SAMPLED_SEARCH_STEPS: usize = 10;
+const MAX_SAMPLED_SEARCH_STEPS: usize = 100;
+
+// Games the human-like solver plays per target. Each one scores its first
+// guess against the whole pool, so this dominates the cost of a rating.
+const HUMAN_GAMES: usize = 8;
+
+// The equations of one length the first guess is chosen from, and that guess
+struct Pool {
+    // Sorted. Every valid equation, or a sample of targets for sampled pools.
+    equations: Vec<String>,
+    opener: String,
+    // For sampled pools, the solver that searches later candidates
+    search: Option<SumzleSolver>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PuzzleGrade {
+    // Valid equations of the target's length, i.e. the candidates before the
+    // first guess, or None for lengths played on samples
+    pub equation_count: Option<usize>,
+    // Guesses the entropy solver needs to find the target, or None if the
+    // target is outside the search space or took too long
+    pub guesses: Option<usize>,
+}
+
+impl PuzzleGrade {
+    // Whether the solver finds the target within the difficulty's attempts
+    pub fn fits(&self, difficulty: Difficulty) -> bool {
+        self.guesses.is_some_and(|guesses| guesses <= difficulty.attempts())
+    }
+}
+
+// How hard a target is to deduce
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct DifficultyRating {
+    // Guesses the entropy solver needs
+    pub optimal_guesses: usize,
+    // Mean and largest number of guesses over the human-like solver's games
+    pub expected_guesses: f64,
+    pub worst_case_guesses: usize,
+    // 1 to 5
+    pub stars: u8,
+}
+
+// Plays an entropy-maximising solver against targets. The pools of valid
+// equations are searched once per length and kept, so grade many targets
+// with one grader. Longer targets are played on samples, which makes their
+// guess counts estimates.
+pub struct PuzzleGrader {
+    max_operand_value: i32,
+    rules: RuleSet,
+    symbols: CharSet,
+    // Longest length searched for a pool; longer targets are played on
+    // samples unless a dictionary for their length was added
+    max_length: usize,
+    pools: HashMap<usize, Pool>,
+}
+
+impl PuzzleGrader {
+    pub fn new(max_operand_value: i32, rules: RuleSet) -> Self {
+        Self {
+            max_operand_value,
+            rules,
+            symbols: CharSet::ALL,
+            max_length: 7,
+            pools: HashMap::new(),
+        }
+    }
+
+    pub fn set_symbols(&mut self, symbols: CharSet) {
+        self.symbols = symbols;
+        self.pools.clear();
+    }
+
+    // Searching a length-7 pool takes seconds and every extra character
+    // multiplies that; longer targets are played on samples unless a
+    // dictionary for their length was added
+    pub fn set_max_length(&mut self, max_length: usize) {
+        self.max_length = max_length;
+    }
+
+    // Use a precomputed dictionary as the pool for its length
+    pub fn add_dictionary(&mut self, dictionary: &Dictionary) -> Result<(), DictionaryError> {
+        let solver = self.solver(dictionary.length());
+        if !dictionary.matches(&solver) {
+            return Err(DictionaryError::Mismatch);
+        }
+        let equations: Vec<String> = dictionary
+            .equations()
+            .iter()
+            .filter(|equation| equation.chars().all(|c| self.symbols.contains(c)))
+            .cloned()
+            .collect();
+        self.pools.insert(dictionary.length(), Pool::new(equations, None));
+        Ok(())
+    }
+
+    fn solver(&self, length: usize) -> SumzleSolver {
+        let mut solver = SumzleSolver::with_symbols(length, self.max_operand_value, self.symbols);
+        solver.set_rules(self.rules);
+        solver
+    }
+
+    fn pool(&mut self, length: usize) -> &Pool {
+        if !self.pools.contains_key(&length) {
+            let pool = if length > self.max_length { self.sampled_pool(length) } else { self.searched_pool(length) };
+            self.pools.insert(length, pool);
+        }
+        &self.pools[&length]
+    }
+
+    fn searched_pool(&self, length: usize) -> Pool {
+        let mut equations = self.solver(length).search(&Constraints::default()).unwrap_or_default();
+        equations.sort_unstable();
+        Pool::new(equations, None)
+    }
+
+    // Targets the game would generate stand in for the pool on the first
+    // guess, or the first equations a search finds if it generates none
+    fn sampled_pool(&self, length: usize) -> Pool {
+        let solver = self.solver(length);
+        let mut generator = PuzzleGenerator::new(length as u64, Difficulty::Medium);
+        generator.set_length(Some(length));
+        generator.set_symbols(self.symbols);
+        generator.set_rules(self.rules);
+        let mut equations: Vec<String> = (0..SAMPLED_TARGETS).filter_map(|_| generator.try_generate()).collect();
+        if equations.is_empty() {
+            equations = search_candidates(&solver, &Constraints::default());
+        }
+        equations.sort_unstable();
+        equations.dedup();
+        Pool::new(equations, Some(solver))
+    }
+
+    // Number of valid equations of `length`, or None for lengths beyond
+    // `max_length` without a dictionary
+    pub fn equation_count(&mut self, length: usize) -> Option<usize> {
+        let pool = self.pool(length);
+        pool.search.is_none().then_some(pool.equations.len())
+    }
+
+    // Play the entropy solver against `target`
+    pub fn grade(&mut self, target: &str) -> PuzzleGrade {
+        let equation_count = self.equation_count(target.chars().count());
+        let pool = self.pool(target.chars().count());
+        let guesses = if pool.contains(target) { pool.play(target) } else { None };
+        PuzzleGrade { equation_count, guesses }
+    }
+
+    // Rate `target` by simulated play: the entropy solver's guess count, and
+    // the mean and worst of several games by a human-like solver that always
+    // guesses a random equation consistent with the feedback so far. None if
+    // the target is outside the search space.
+    pub fn rate_difficulty(&mut self, target: &str) -> Option<DifficultyRating> {
+        let pool = self.pool(target.chars().count());
+        if !pool.contains(target) {
+            return None;
+        }
+        let optimal_guesses = pool.play(target).unwrap_or(MAX_SIMULATED_GUESSES + 1);
+
+        // Seeded by the target, so a rating is reproducible
+        let seed = target.bytes().fold(0xCBF2_9CE4_8422_2325u64, |hash, b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01B3));
+        let mut rng = SplitMix64::new(seed);
+        let games: Vec<usize> = (0..HUMAN_GAMES)
+            .map(|_| pool.play_with(target, |_, candidates| Some(rng.pick(candidates).clone())).unwrap_or(MAX_SIMULATED_GUESSES + 1))
+            .collect();
+        let expected_guesses = games.iter().sum::<usize>() as f64 / games.len() as f64;
+        let worst_case_guesses = games.iter().copied().max().unwrap_or(0);
+
+        Some(DifficultyRating {
+            optimal_guesses,
+            expected_guesses,
+            worst_case_guesses,
+            stars: stars(optimal_guesses, expected_guesses, worst_case_guesses),
+        })
+    }
+}
+
+// Mean human guesses, raised by half a guess each when a human can run out of
+// rows on medium or the entropy solver would on hard, binned into 1 to 5
+// stars. The bins spread typical 5 to 7 character targets across the range.
+fn stars(optimal_guesses: usize, expected_guesses: f64, worst_case_guesses: usize) -> u8 {
+    let mut score = expected_guesses;
+    if worst_case_guesses > Difficulty::Medium.attempts() {
+        score += 0.5;
+    }
+    if optimal_guesses > Difficulty::Hard.attempts() {
+        score += 0.5;
+    }
+    match score {
+        s if s < 3.5 => 1,
+        s if s < 4.25 => 2,
+        s if s < 5.0 => 3,
+        s if s < 5.75 => 4,
+        _ => 5,
+    }
+}
+
+impl Pool {
+    fn new(equations: Vec<String>, search: Option<SumzleSolver>) -> Self {
+        let opener = best_guess(&equations).unwrap_or_default();
+        Self { equations, opener, search }
+    }
+
+    fn contains(&self, target: &str) -> bool {
+        match &self.search {
+            None => self.equations.binary_search_by(|e| e.as_str().cmp(target)).is_ok(),
+            Some(solver) => {
+                let solved = Constraints { rows: vec![to_row(target, &score(target, target))] };
+                solver.search(&solved).is_ok_and(|found| !found.is_empty())
+            },
+        }
+    }
+
+    fn play(&self, target: &str) -> Option<usize> {
+        self.play_with(target, |turn, candidates| if turn == 1 { Some(self.opener.clone()) } else { best_guess(candidates) })
+    }
+
+    // A game where `choose` picks each guess from the candidates left
+    fn play_with(&self, target: &str, mut choose: impl FnMut(usize, &[String]) -> Option<String>) -> Option<usize> {
+        let mut candidates: Vec<String> = Vec::new();
+        let mut constraints = Constraints::default();
+        for turn in 1..=MAX_SIMULATED_GUESSES {
+            let remaining = if turn == 1 { &self.equations } else { &candidates };
+            if remaining.is_empty() {
+                return None;
+            }
+            let guess = choose(turn, remaining)?;
+            if guess == target {
+                return Some(turn);
+            }
+            let feedback = score(&guess, target);
+            candidates = match &self.search {
+                None => remaining.iter().filter(|c| score(&guess, c) == feedback).cloned().collect(),
+                Some(solver) => {
+                    constraints.rows.push(to_row(&guess, &feedback));
+                    search_candidates(solver, &constraints)
+                },
+            };
+        }
+        None
+    }
+}
+
+// Up to `SAMPLE_CANDIDATES` equations satisfying `constraints`, the first a
+// bounded search finds; empty if it finds none within its limit
+fn search_candidates(solver: &SumzleSolver, constraints: &Constraints) -> Vec<String> {
+    let Ok(mut session) = solver.start_search(constraints) else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    for steps in 1..=MAX_SAMPLED_SEARCH_STEPS {
+        let step = session.step(SAMPLED_SEARCH_STEP);
+        found.extend(step.new_results);
+        if step.done || found.len() >= SAMPLE_CANDIDATES || (steps >= SAMPLED_SEARCH_STEPS && !found.is_empty()) {
+            break;
+        }
+    }
+    found.truncate(SAMPLE_CANDIDATES);
+    found
+}
+
+// The candidate with the most expected information about the others
+fn best_guess(candidates: &[String]) -> Option<String> {
+    if candidates.len() <= 2 {
+        return candidates.first().cloned();
+    }
+    let guesses = evenly_spaced(candidates, SAMPLE_GUESSES);
+    let sample = evenly_spaced(candidates, SAMPLE_CANDIDATES);
+    rank_guesses(&guesses, &sample, 1, RecommendMode::Entropy).into_iter().next().map(|best| best.guess)
+}
+
+fn evenly_spaced(items: &[String], n: usize) -> Vec<String> {
+    if items.len() <= n {
+        return items.to_vec();
+    }
+    (0..n).map(|i| items[i * items.len() / n].clone()).collect()
+}
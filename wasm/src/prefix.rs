@@ -0,0 +1,168 @@
+// Running evaluation of the expression the search is building: the Pratt
+// parser of `expr.rs` in shunting-yard form, extended one character at a time
+// so that each operand is evaluated once, by the character that completes it
+
+use crate::error::EvalError;
+use crate::expr::{infix_binding_power, BinOp, Token, PREFIX_NEG_BP};
+use crate::solver::SumzleSolver;
+use crate::value::Value;
+
+// An operator still waiting for its right operand, or an open bracket
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pending {
+    Infix(Token, u8),
+    Neg,
+    Plus,
+    Open(char),
+}
+
+impl Pending {
+    // The operator takes everything that follows while it binds more tightly
+    // than this; brackets wait for their closing bracket instead
+    fn right_binding_power(&self) -> u8 {
+        match *self {
+            Pending::Infix(_, bp) => bp,
+            Pending::Neg | Pending::Plus => PREFIX_NEG_BP,
+            Pending::Open(_) => 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct PrefixEvaluation {
+    // Operands evaluated so far, most recent last
+    values: Vec<Value>,
+    pending: Vec<Pending>,
+    // The number being read
+    number: Option<i64>,
+    // Whether the last character ends an operand, making `+`/`-` binary
+    operand_ended: bool,
+    left_side: Option<Value>,
+}
+
+impl Clone for PrefixEvaluation {
+    fn clone(&self) -> Self {
+        Self { values: self.values.clone(), pending: self.pending.clone(), ..*self }
+    }
+
+    // The search copies the state of one position into the next on every
+    // placement; this keeps the buffers it already has
+    fn clone_from(&mut self, source: &Self) {
+        self.values.clone_from(&source.values);
+        self.pending.clone_from(&source.pending);
+        self.number = source.number;
+        self.operand_ended = source.operand_ended;
+        self.left_side = source.left_side;
+    }
+}
+
+impl PrefixEvaluation {
+    // The value of the left side, once the relation is placed
+    pub(crate) fn left_side(&self) -> Option<Value> {
+        self.left_side
+    }
+
+    // Extend the expression by `c`, which the search has already found
+    // placeable. Fails if an operand `c` completes cannot be evaluated, or if
+    // no completion can parse. Returns the left side when `c` is the
+    // relation, and the sum so far when it is a top-level binary `+`/`-` of
+    // the left side.
+    pub(crate) fn push(&mut self, c: char, solver: &SumzleSolver) -> Result<Option<Value>, EvalError> {
+        if let Some(digit) = c.to_digit(10) {
+            let number = self.number.unwrap_or(0).checked_mul(10).and_then(|n| n.checked_add(digit as i64)).ok_or(EvalError::Overflow)?;
+            self.number = Some(number);
+            self.operand_ended = true;
+            return Ok(None);
+        }
+        if let Some(number) = self.number.take() {
+            self.values.push(Value::integer(number as i128, solver.rules().js_numbers));
+        }
+        let operand_ended = std::mem::replace(&mut self.operand_ended, matches!(c, ')' | ']' | '!'));
+
+        match c {
+            '(' | '[' => self.pending.push(Pending::Open(c)),
+            '+' if !operand_ended => self.pending.push(Pending::Plus),
+            '-' if !operand_ended => self.pending.push(Pending::Neg),
+            _ if !operand_ended => return Err(EvalError::Syntax),
+            ')' | ']' => {
+                self.reduce(0, solver)?;
+                let open = if c == ')' { '(' } else { '[' };
+                if self.pending.pop() != Some(Pending::Open(open)) {
+                    return Err(EvalError::Syntax);
+                }
+                if c == ']' {
+                    let value = self.pop()?;
+                    self.values.push(solver.floor(value));
+                }
+            },
+            '!' => {
+                self.reduce(solver.grammar().bang_binding_power(), solver)?;
+                let value = self.pop()?;
+                self.values.push(solver.factorial(value)?);
+            },
+            '=' | '>' => {
+                self.reduce(0, solver)?;
+                if !self.pending.is_empty() || self.values.len() != 1 || self.left_side.is_some() {
+                    return Err(EvalError::Syntax);
+                }
+                self.left_side = self.values.pop();
+                return Ok(self.left_side);
+            },
+            _ => {
+                let token = match c {
+                    '+' => Token::Plus,
+                    '-' => Token::Minus,
+                    '*' => Token::Star,
+                    '/' => Token::Slash,
+                    '%' => Token::Percent,
+                    '^' => Token::Caret,
+                    'A' => Token::Perm,
+                    _ => return Err(EvalError::Syntax),
+                };
+                let (left_bp, right_bp) = infix_binding_power(token).ok_or(EvalError::Syntax)?;
+                self.reduce(left_bp, solver)?;
+                let sum = (self.pending.is_empty() && self.left_side.is_none() && matches!(token, Token::Plus | Token::Minus))
+                    .then(|| self.values.last().copied())
+                    .flatten();
+                self.pending.push(Pending::Infix(token, right_bp));
+                return Ok(sum);
+            },
+        }
+        Ok(None)
+    }
+
+    // Apply the pending operators that bind more tightly than an operator of
+    // left binding power `min_bp`, as the parser would on reading it
+    fn reduce(&mut self, min_bp: u8, solver: &SumzleSolver) -> Result<(), EvalError> {
+        while let Some(&pending) = self.pending.last() {
+            if pending.right_binding_power() <= min_bp {
+                break;
+            }
+            self.pending.pop();
+            let value = match pending {
+                Pending::Neg => self.pop()?.checked_neg()?,
+                Pending::Plus => self.pop()?,
+                Pending::Infix(token, _) => {
+                    let b = self.pop()?;
+                    let a = self.pop()?;
+                    match token {
+                        Token::Perm => solver.permutation(a, b)?,
+                        Token::Plus => solver.binary(BinOp::Add, a, b)?,
+                        Token::Minus => solver.binary(BinOp::Sub, a, b)?,
+                        Token::Star => solver.binary(BinOp::Mul, a, b)?,
+                        Token::Slash => solver.binary(BinOp::Div, a, b)?,
+                        Token::Percent => solver.binary(BinOp::Mod, a, b)?,
+                        _ => solver.binary(BinOp::Pow, a, b)?,
+                    }
+                },
+                Pending::Open(_) => unreachable!(),
+            };
+            self.values.push(value);
+        }
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Value, EvalError> {
+        self.values.pop().ok_or(EvalError::Syntax)
+    }
+}
@@ -0,0 +1,178 @@
+// Exact rational arithmetic with overflow checking
+//
+// All operations report an error when the exact result does not fit in i128
+// (or, for powers, is not a rational number) instead of rounding.
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArithmeticError {
+    Overflow,
+    DivisionByZero,
+    Irrational,
+}
+
+use ArithmeticError::*;
+
+type Result<T> = std::result::Result<T, ArithmeticError>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a.abs()
+}
+
+// Exact integer q-th root of a non-negative integer, if there is one
+fn exact_root(n: i128, q: u32) -> Option<i128> {
+    if n < 2 || q == 1 {
+        return Some(n);
+    }
+    let (mut lo, mut hi) = (1i128, n.min(1 << (127 / q + 1)));
+    while lo <= hi {
+        let mid = lo + (hi - lo) / 2;
+        match mid.checked_pow(q) {
+            Some(p) if p == n => return Some(mid),
+            Some(p) if p < n => lo = mid + 1,
+            _ => hi = mid - 1,
+        }
+    }
+    None
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Result<Self> {
+        if den == 0 {
+            return Err(DivisionByZero);
+        }
+        let g = gcd(num, den);
+        let (mut num, mut den) = (num / g, den / g);
+        if den < 0 {
+            num = num.checked_neg().ok_or(Overflow)?;
+            den = den.checked_neg().ok_or(Overflow)?;
+        }
+        Ok(Self { num, den })
+    }
+
+    pub fn from_integer(n: i128) -> Self {
+        Self { num: n, den: 1 }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        if self.is_integer() { Some(self.num) } else { None }
+    }
+
+    pub fn checked_neg(self) -> Result<Self> {
+        Ok(Self { num: self.num.checked_neg().ok_or(Overflow)?, den: self.den })
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self> {
+        let g = gcd(self.den, other.den);
+        let lhs = self.num.checked_mul(other.den / g).ok_or(Overflow)?;
+        let rhs = other.num.checked_mul(self.den / g).ok_or(Overflow)?;
+        let den = self.den.checked_mul(other.den / g).ok_or(Overflow)?;
+        Self::new(lhs.checked_add(rhs).ok_or(Overflow)?, den)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Result<Self> {
+        // Cross-reduce first to keep intermediates small
+        let g1 = gcd(self.num, other.den).max(1);
+        let g2 = gcd(other.num, self.den).max(1);
+        let num = (self.num / g1).checked_mul(other.num / g2).ok_or(Overflow)?;
+        let den = (self.den / g2).checked_mul(other.den / g1).ok_or(Overflow)?;
+        Self::new(num, den)
+    }
+
+    pub fn checked_div(self, other: Self) -> Result<Self> {
+        self.checked_mul(Self::new(other.den, other.num)?)
+    }
+
+    // Remainder with the sign of the dividend: a - b * trunc(a / b)
+    pub fn checked_rem(self, other: Self) -> Result<Self> {
+        let quotient = self.checked_div(other)?.trunc();
+        self.checked_sub(other.checked_mul(quotient)?)
+    }
+
+    pub fn floor(self) -> Self {
+        Self { num: self.num.div_euclid(self.den), den: 1 }
+    }
+
+    pub fn trunc(self) -> Self {
+        Self { num: self.num / self.den, den: 1 }
+    }
+
+    // Exact power; fractional exponents are only allowed when the root is
+    // exact, e.g. 4^(1/2) = 2
+    pub fn checked_pow(self, exponent: Self) -> Result<Self> {
+        let base = if exponent.den == 1 {
+            self
+        } else {
+            let q = u32::try_from(exponent.den).map_err(|_| Irrational)?;
+            if self.num < 0 && q % 2 == 0 {
+                return Err(Irrational);
+            }
+            let num = exact_root(self.num.checked_abs().ok_or(Overflow)?, q).ok_or(Irrational)?;
+            let den = exact_root(self.den, q).ok_or(Irrational)?;
+            Self::new(if self.num < 0 { -num } else { num }, den)?
+        };
+
+        let p = exponent.num;
+        if base.is_zero() {
+            return match p {
+                0 => Ok(Self::ONE),
+                p if p > 0 => Ok(Self::ZERO),
+                _ => Err(DivisionByZero),
+            };
+        }
+        if base.den == 1 && (base.num == 1 || base.num == -1) {
+            let odd = p % 2 != 0;
+            return Ok(Self::from_integer(if base.num == -1 && odd { -1 } else { 1 }));
+        }
+
+        let e = p.checked_abs().and_then(|e| u32::try_from(e).ok()).ok_or(Overflow)?;
+        let num = base.num.checked_pow(e).ok_or(Overflow)?;
+        let den = base.den.checked_pow(e).ok_or(Overflow)?;
+        if p < 0 { Self::new(den, num) } else { Self::new(num, den) }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
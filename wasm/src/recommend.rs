@@ -0,0 +1,160 @@
+// Picking a recommended next guess from the candidate list
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use serde::Serialize;
+
+use crate::feedback::{score, TileState};
+
+// The AK page's heuristic: sum the percentage of candidates containing each
+// distinct character of a guess, plus 50 for each of the five most common
+// characters it contains. The first best-scoring candidate wins.
+pub fn char_frequency_pick(candidates: &[String]) -> Option<&str> {
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let mut char_counts: HashMap<char, usize> = HashMap::new();
+    for candidate in candidates {
+        for c in candidate.chars().collect::<BTreeSet<_>>() {
+            *char_counts.entry(c).or_insert(0) += 1;
+        }
+    }
+
+    let total = candidates.len() as f64;
+    let mut probabilities: Vec<(char, f64)> = char_counts
+        .into_iter()
+        .map(|(c, count)| (c, count as f64 / total * 100.0))
+        .collect();
+    probabilities.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let probability: HashMap<char, f64> = probabilities.iter().copied().collect();
+    let top_chars: Vec<char> = probabilities.iter().take(5).map(|&(c, _)| c).collect();
+
+    let mut best: Option<&str> = None;
+    let mut best_score = -1.0;
+    for candidate in candidates {
+        let unique: BTreeSet<char> = candidate.chars().collect();
+        let mut score: f64 = unique.iter().map(|c| probability[c]).sum();
+        score += 50.0 * top_chars.iter().filter(|c| unique.contains(c)).count() as f64;
+
+        if score > best_score {
+            best_score = score;
+            best = Some(candidate);
+        }
+    }
+
+    best
+}
+
+// How suggested guesses are ranked
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecommendMode {
+    // Highest expected information first
+    #[default]
+    Entropy,
+    // Smallest worst-case remaining bucket first
+    Minimax,
+}
+
+impl RecommendMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "entropy" => Some(RecommendMode::Entropy),
+            "minimax" => Some(RecommendMode::Minimax),
+            _ => None,
+        }
+    }
+}
+
+// A suggested guess with the expected information (in bits) its feedback
+// gives about the current candidates and the size of the largest group of
+// candidates that could remain after it
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GuessScore {
+    pub guess: String,
+    pub score: f64,
+    pub worst_case: usize,
+    pub is_candidate: bool,
+}
+
+// Feedback pattern of a guess against a target packed as base-3 digits
+pub fn pattern_code(guess: &str, target: &str) -> u64 {
+    score(guess, target).iter().fold(0, |code, state| {
+        code * 3 + match state {
+            TileState::Absent => 0,
+            TileState::Present => 1,
+            TileState::Correct => 2,
+        }
+    })
+}
+
+// Sizes of the buckets the candidates fall into by the feedback `guess` gets
+pub fn feedback_partition(guess: &str, candidates: &[String]) -> HashMap<u64, usize> {
+    let mut buckets = HashMap::new();
+    for target in candidates {
+        *buckets.entry(pattern_code(guess, target)).or_insert(0) += 1;
+    }
+    buckets
+}
+
+// Shannon entropy of the partition, with candidates equally likely. Summed
+// in a fixed order, so equal partitions score exactly the same and ties do
+// not depend on the map's iteration order.
+fn partition_entropy(buckets: &HashMap<u64, usize>, total: usize) -> f64 {
+    let mut sizes: Vec<usize> = buckets.values().copied().collect();
+    sizes.sort_unstable();
+    sizes
+        .into_iter()
+        .map(|n| {
+            let p = n as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+pub fn expected_information(guess: &str, candidates: &[String]) -> f64 {
+    partition_entropy(&feedback_partition(guess, candidates), candidates.len())
+}
+
+// Largest number of candidates that can remain after playing `guess`
+pub fn worst_case_remaining(guess: &str, candidates: &[String]) -> usize {
+    feedback_partition(guess, candidates).values().copied().max().unwrap_or(0)
+}
+
+// Score every guess in `pool` and return the best `top_n` for `mode`. Ties
+// fall back to the other measure, then prefer guesses that can still be the
+// answer, then pool order.
+pub fn rank_guesses(pool: &[String], candidates: &[String], top_n: usize, mode: RecommendMode) -> Vec<GuessScore> {
+    let candidate_set: HashSet<&str> = candidates.iter().map(String::as_str).collect();
+    let mut scored: Vec<GuessScore> = pool
+        .iter()
+        .map(|guess| {
+            let buckets = feedback_partition(guess, candidates);
+            GuessScore {
+                guess: guess.clone(),
+                score: partition_entropy(&buckets, candidates.len()),
+                worst_case: buckets.values().copied().max().unwrap_or(0),
+                is_candidate: candidate_set.contains(guess.as_str()),
+            }
+        })
+        .collect();
+
+    scored.sort_by(|a, b| {
+        let by_entropy = b.score.total_cmp(&a.score);
+        let by_worst_case = a.worst_case.cmp(&b.worst_case);
+        match mode {
+            RecommendMode::Entropy => by_entropy.then(by_worst_case),
+            RecommendMode::Minimax => by_worst_case.then(by_entropy),
+        }
+        .then(b.is_candidate.cmp(&a.is_candidate))
+    });
+    scored.truncate(top_n);
+    scored
+}
+
+pub fn rank_by_entropy(pool: &[String], candidates: &[String], top_n: usize) -> Vec<GuessScore> {
+    rank_guesses(pool, candidates, top_n, RecommendMode::Entropy)
+}
+
+pub fn rank_by_worst_case(pool: &[String], candidates: &[String], top_n: usize) -> Vec<GuessScore> {
+    rank_guesses(pool, candidates, top_n, RecommendMode::Minimax)
+}
@@ -0,0 +1,36 @@
+// Small seedable random number generator (SplitMix64). It uses only integer
+// arithmetic, so a seed gives the same numbers on native and wasm builds.
+
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1), the stand-in for `Math.random()`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Uniform in `min..=max`, the stand-in for the game's `getRandomInt`
+    pub fn random_int(&mut self, min: i64, max: i64) -> i64 {
+        let span = (max - min + 1) as u64;
+        min + ((self.next_u64() as u128 * span as u128) >> 64) as i64
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.random_int(0, items.len() as i64 - 1) as usize]
+    }
+}
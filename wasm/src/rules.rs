@@ -0,0 +1,111 @@
+// Rule sets deciding which equations are accepted
+
+// How floor brackets `[x]` round a fractional value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloorRounding {
+    // Towards negative infinity, [-7/2] = -4
+    Floor,
+    // Towards zero like the game's `Math.trunc`, [-7/2] = -3
+    Truncate,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RuleSet {
+    pub floor_rounding: FloorRounding,
+    // Largest n accepted by `n!`
+    pub max_factorial: i128,
+    // Largest absolute value either side of an equation may have
+    pub max_abs_value: i128,
+    // Operand rules of the game's regex rewriting in `safeEval`:
+    // - `A` binds tighter than `!` (3A2! = (3A2)!) and only takes numbers or
+    //   parenthesized operands
+    // - `!` cannot be stacked and floor brackets cannot be nested
+    // - a unary sign cannot precede a `^` base (-2^2) or another sign (2--2)
+    // - `mA0` is 1 for any m, and the divisor of `%` must be an integer
+    pub game_operand_rules: bool,
+    // Search only: floor brackets hold a single `a/b` and take no `!`
+    pub floor_division_only: bool,
+    // Evaluate with JS numbers like the game's `safeEval`: doubles that lose
+    // integer precision above 2^53 and give NaN or Infinity instead of
+    // failing, accepted by `isValidEquation`'s rules (no NaN side, `=` within
+    // 0.0001, each side within 0.0001 of an integer)
+    pub js_numbers: bool,
+}
+
+impl RuleSet {
+    // The solver's own rules
+    pub fn classic() -> Self {
+        Self {
+            floor_rounding: FloorRounding::Floor,
+            max_factorial: 12,
+            max_abs_value: i32::MAX as i128,
+            game_operand_rules: false,
+            floor_division_only: true,
+            js_numbers: false,
+        }
+    }
+
+    // Reproduces the accept/reject decisions of `isValidEquation` in Sumzle.html
+    pub fn game_compatible() -> Self {
+        Self {
+            floor_rounding: FloorRounding::Truncate,
+            max_factorial: 20,
+            // Doubles overflow to Infinity rather than failing
+            max_abs_value: i128::MAX,
+            game_operand_rules: true,
+            floor_division_only: false,
+            js_numbers: true,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "classic" => Some(Self::classic()),
+            "game" | "game_compatible" => Some(Self::game_compatible()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+// The game's difficulty levels
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    // Rows the player gets, as in the game's `difficultySetting`
+    pub fn attempts(&self) -> usize {
+        match self {
+            Difficulty::Easy => 8,
+            Difficulty::Medium => 6,
+            Difficulty::Hard => 4,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "medium" => Some(Difficulty::Medium),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Resumable search that can be advanced a bounded amount at a time
+
+use serde::Serialize;
+
+use crate::alphabet::CharCounts;
+use crate::prefix::PrefixEvaluation;
+use crate::solver::{ConstraintError, Constraints, FloorContext, GlobalKnowledge, SumzleSolver};
+
+// One position of the expression under construction: the characters to try
+// there, how far through them we are, and the state the position was entered with
+struct Frame {
+    chars: Vec<char>,
+    next: usize,
+    placed: Option<char>,
+    main_op_so_far: Option<char>,
+    floor_context: FloorContext,
+}
+
+// Outcome of one `SearchSession::step`
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct StepResult {
+    // Solutions found during this step
+    pub new_results: Vec<String>,
+    // Complete expressions examined since the search started
+    pub searched: usize,
+    pub done: bool,
+}
+
+// Depth-first search over the same space and in the same order as
+// `SumzleSolver::search`, with the recursion kept on an explicit stack so
+// the caller decides how much work each call does
+pub struct SearchSession {
+    solver: SumzleSolver,
+    gk: GlobalKnowledge,
+    current_expression: Vec<char>,
+    current_expression_counts: CharCounts,
+    // Brackets opened and not yet closed, innermost last
+    open_brackets: Vec<char>,
+    // Evaluation of the first `i` characters at `i`
+    evaluations: Vec<PrefixEvaluation>,
+    stack: Vec<Frame>,
+    // Right-hand side computed for the current left side, starting at `rhs_start`
+    rhs: Vec<char>,
+    rhs_start: usize,
+    searched: usize,
+}
+
+impl SearchSession {
+    pub fn new(solver: &SumzleSolver, constraints: &Constraints) -> Result<Self, ConstraintError> {
+        Self::with_prefix(solver, constraints, &[])
+    }
+
+    // Search only the subtree below `prefix`, a placement of the first
+    // positions as produced by `prefixes`
+    pub fn with_prefix(solver: &SumzleSolver, constraints: &Constraints, prefix: &[char]) -> Result<Self, ConstraintError> {
+        let gk = solver.preprocess_constraints(constraints)?;
+        Ok(Self::from_knowledge(solver, gk, prefix))
+    }
+
+    pub(crate) fn from_knowledge(solver: &SumzleSolver, gk: GlobalKnowledge, prefix: &[char]) -> Self {
+        let mut session = Self {
+            solver: solver.clone(),
+            gk,
+            current_expression: vec!['\0'; solver.length()],
+            current_expression_counts: CharCounts::default(),
+            open_brackets: Vec::new(),
+            evaluations: vec![PrefixEvaluation::default(); solver.length() + 1],
+            stack: Vec::new(),
+            rhs: Vec::new(),
+            rhs_start: 0,
+            searched: 0,
+        };
+        if solver.length() == 0 || prefix.len() >= solver.length() {
+            return session;
+        }
+
+        // Lay the prefix down as frames with nothing left to try, so the
+        // search ends once the subtree below it is exhausted
+        let mut main_op_so_far = None;
+        let mut floor_context = FloorContext::default();
+        for (index, &c) in prefix.iter().enumerate() {
+            if !solver.can_place_char(c, index, &session.current_expression, main_op_so_far, &session.current_expression_counts, &session.open_brackets, &floor_context, &session.gk)
+                || !session.advance_evaluation(index, c)
+            {
+                session.cancel();
+                return session;
+            }
+            session.stack.push(Frame { chars: vec![c], next: 1, placed: Some(c), main_op_so_far, floor_context });
+            session.place(index, c);
+            if solver.is_main_operator(c) {
+                main_op_so_far = Some(c);
+            }
+            floor_context = floor_context.after(c);
+        }
+        session.push_frame(main_op_so_far, floor_context);
+        session
+    }
+
+    // Every placeable prefix of `depth` characters, in search order. The
+    // subtrees below them partition the search; `depth` is capped so that
+    // prefixes never cover the whole expression.
+    pub fn prefixes(solver: &SumzleSolver, constraints: &Constraints, depth: usize) -> Result<Vec<Vec<char>>, ConstraintError> {
+        let gk = solver.preprocess_constraints(constraints)?;
+        Ok(Self::prefixes_for(solver, gk, depth))
+    }
+
+    pub(crate) fn prefixes_for(solver: &SumzleSolver, gk: GlobalKnowledge, depth: usize) -> Vec<Vec<char>> {
+        let depth = depth.min(solver.length().saturating_sub(1));
+        if depth == 0 {
+            return vec![Vec::new()];
+        }
+        let mut session = Self::from_knowledge(solver, gk, &[]);
+        let mut prefixes = Vec::new();
+        session.run(usize::MAX, depth, |session, _| prefixes.push(session.current_expression[..depth].to_vec()));
+        prefixes
+    }
+
+    fn push_frame(&mut self, main_op_so_far: Option<char>, floor_context: FloorContext) {
+        let index = self.stack.len();
+        let chars = if main_op_so_far == Some('=') {
+            // Compute the right-hand side instead of enumerating digits
+            if self.current_expression[index - 1] == '=' {
+                let width = self.current_expression.len() - index;
+                self.rhs = self.evaluations[index].left_side().and_then(|lhs| self.solver.right_hand_side(lhs, width)).unwrap_or_default();
+                self.rhs_start = index;
+            }
+            self.rhs.get(index - self.rhs_start).map(|&c| vec![c]).unwrap_or_default()
+        } else {
+            self.solver.get_optimized_char_order(index, &self.current_expression, main_op_so_far, &floor_context, &self.gk)
+        };
+        self.stack.push(Frame { chars, next: 0, placed: None, main_op_so_far, floor_context });
+    }
+
+    // Evaluate the expression with `c` at `index` from the evaluation of the
+    // characters before it
+    fn advance_evaluation(&mut self, index: usize, c: char) -> bool {
+        let (before, after) = self.evaluations.split_at_mut(index + 1);
+        after[0].clone_from(&before[index]);
+        self.solver.advance_evaluation(&mut after[0], c, index, &self.gk)
+    }
+
+    fn place(&mut self, index: usize, c: char) {
+        self.current_expression[index] = c;
+        self.current_expression_counts.add(c);
+        match c {
+            '(' | '[' => self.open_brackets.push(c),
+            ')' | ']' => {
+                self.open_brackets.pop();
+            },
+            _ => {},
+        }
+    }
+
+    // Take back the last character placed
+    fn unplace(&mut self, c: char) {
+        self.current_expression_counts.remove(c);
+        match c {
+            '(' | '[' => {
+                self.open_brackets.pop();
+            },
+            ')' => self.open_brackets.push('('),
+            ']' => self.open_brackets.push('['),
+            _ => {},
+        }
+    }
+
+    // Try at most `budget` character placements, returning the solutions
+    // found along the way
+    pub fn step(&mut self, budget: usize) -> StepResult {
+        let mut new_results = Vec::new();
+        let depth = self.current_expression.len();
+        self.run(budget, depth, |session, main_op_so_far| {
+            if let Some(solution) = session.solver.accept_complete(&session.current_expression, main_op_so_far, &session.current_expression_counts, &session.gk) {
+                new_results.push(solution);
+            }
+        });
+
+        StepResult { new_results, searched: self.searched, done: self.is_done() }
+    }
+
+    // Advance the depth-first search by at most `budget` placements, handing
+    // every placement that fills `depth` positions to `visit` instead of
+    // descending further
+    fn run(&mut self, budget: usize, depth: usize, mut visit: impl FnMut(&Self, Option<char>)) {
+        let mut attempts = 0;
+
+        while attempts < budget {
+            let index = match self.stack.len() {
+                0 => break,
+                len => len - 1,
+            };
+
+            // Undo the character placed here on the previous visit
+            if let Some(previous) = self.stack[index].placed.take() {
+                self.unplace(previous);
+            }
+
+            let frame = &mut self.stack[index];
+            if frame.next == frame.chars.len() {
+                self.stack.pop();
+                continue;
+            }
+            let char_to_try = frame.chars[frame.next];
+            frame.next += 1;
+            attempts += 1;
+
+            let main_op_so_far = frame.main_op_so_far;
+            let floor_context = frame.floor_context;
+            if !self.solver.can_place_char(char_to_try, index, &self.current_expression, main_op_so_far, &self.current_expression_counts, &self.open_brackets, &floor_context, &self.gk)
+                || !self.advance_evaluation(index, char_to_try)
+            {
+                continue;
+            }
+
+            self.stack[index].placed = Some(char_to_try);
+            self.place(index, char_to_try);
+
+            let new_main_op = if self.solver.is_main_operator(char_to_try) { Some(char_to_try) } else { main_op_so_far };
+
+            if index + 1 == depth {
+                if depth == self.current_expression.len() {
+                    self.searched += 1;
+                }
+                visit(self, new_main_op);
+            } else {
+                self.push_frame(new_main_op, floor_context.after(char_to_try));
+            }
+        }
+    }
+
+    // Stop the search; further steps return nothing
+    pub fn cancel(&mut self) {
+        self.stack.clear();
+        self.current_expression_counts = CharCounts::default();
+        self.open_brackets.clear();
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    pub fn searched(&self) -> usize {
+        self.searched
+    }
+}
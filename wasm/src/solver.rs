@@ -0,0 +1,1230 @@
+// Pure Rust solver core, independent of wasm-bindgen
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use serde::{Serialize, Deserialize};
+
+use crate::alphabet::{symbol_index, CharCounts, CharSet, SYMBOLS, SYMBOL_COUNT};
+use crate::bounds::{expression_ranges, Interval};
+use crate::dictionary::{Dictionary, DictionaryError};
+use crate::error::EvalError;
+use crate::expr::{parse_equation_with, parse_expression_with, BinOp, Expr, Grammar, Relation};
+use crate::prefix::PrefixEvaluation;
+use crate::recommend::{rank_guesses, GuessScore, RecommendMode};
+use crate::rules::{FloorRounding, RuleSet};
+use crate::session::{SearchSession, StepResult};
+use crate::value::Value;
+
+// How close the game's `isValidEquation` takes two numbers to be equal
+const JS_TOLERANCE: f64 = 0.0001;
+
+// Constraint data structure for the search algorithm
+#[derive(Clone)]
+pub(crate) struct GlobalKnowledge {
+    fixed_chars: Vec<Option<char>>,
+    cannot_be_at: Vec<CharSet>,
+    globally_forbidden: CharSet,
+    // Exact number of occurrences by symbol index, where known
+    exact_counts: [Option<u8>; SYMBOL_COUNT],
+    // Characters that must appear, with how often
+    required_counts: Vec<(char, usize)>,
+    // Bounds on the value of an expression of each width
+    expression_ranges: Vec<Option<Interval>>,
+    // Fixed characters and unfixed positions after each position
+    fixed_after: Vec<CharCounts>,
+    free_after: Vec<usize>,
+}
+
+impl GlobalKnowledge {
+    fn exact_count(&self, c: char) -> Option<usize> {
+        symbol_index(c).and_then(|i| self.exact_counts[i]).map(usize::from)
+    }
+
+    fn counts_satisfied(&self, counts: &CharCounts) -> bool {
+        SYMBOLS.iter().all(|&c| self.exact_count(c).is_none_or(|exact| counts.get(c) == exact))
+            && self.required_counts.iter().all(|&(c, required)| counts.get(c) >= required)
+    }
+
+    // Whether a complete expression meets every positional and count
+    // constraint, i.e. is consistent with the feedback of every row
+    fn admits(&self, expression: &str) -> bool {
+        if expression.chars().count() != self.fixed_chars.len() {
+            return false;
+        }
+        let mut counts = CharCounts::default();
+        for (i, c) in expression.chars().enumerate() {
+            if self.globally_forbidden.contains(c) || self.cannot_be_at[i].contains(c) || self.fixed_chars[i].is_some_and(|fixed| fixed != c) {
+                return false;
+            }
+            counts.add(c);
+        }
+        self.counts_satisfied(&counts)
+    }
+}
+
+// Results of the last `search`, reused when the next one only adds rows.
+// Clones start with an empty cache, and setters that change the search
+// space clear it.
+#[derive(Default)]
+struct SearchCache(Mutex<Option<CachedSearch>>);
+
+struct CachedSearch {
+    rows: Vec<Row>,
+    results: Vec<String>,
+}
+
+impl SearchCache {
+    fn clear(&mut self) {
+        *self.0.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
+
+impl Clone for SearchCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+// Context for floor brackets
+#[derive(Clone, Copy, Default)]
+pub(crate) struct FloorContext {
+    in_floor: bool,
+    has_slash_in_current_floor: bool,
+}
+
+impl FloorContext {
+    // Context for the position after `c`
+    pub(crate) fn after(self, c: char) -> Self {
+        if c == '[' {
+            FloorContext { in_floor: true, has_slash_in_current_floor: false }
+        } else if c == ']' && self.in_floor {
+            FloorContext { in_floor: false, has_slash_in_current_floor: false }
+        } else if c == '/' && self.in_floor {
+            FloorContext { in_floor: true, has_slash_in_current_floor: true }
+        } else {
+            self
+        }
+    }
+}
+
+// Tile data structure for parsing constraints
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Tile {
+    pub char: String,
+    pub state: String,
+}
+
+// Row data structure for parsing constraints
+pub type Row = Vec<Tile>;
+
+// Constraints data structure for parsing from JSON
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Constraints {
+    pub rows: Vec<Row>,
+}
+
+impl Constraints {
+    pub fn from_json(constraints_json: &str) -> Result<Self, ConstraintError> {
+        serde_json::from_str(constraints_json).map_err(|e| ConstraintError::Parse(e.to_string()))
+    }
+
+    // Parse rows written as `guess:states`, e.g. `12+3=15:GYBBBGG`, where
+    // G is correct, Y is present and B (or `.`) is absent
+    pub fn from_compact<S: AsRef<str>>(rows: &[S]) -> Result<Self, ConstraintError> {
+        rows.iter().map(|r| Self::parse_compact_row(r.as_ref())).collect::<Result<_, _>>().map(|rows| Self { rows })
+    }
+
+    fn parse_compact_row(row: &str) -> Result<Row, ConstraintError> {
+        let (guess, states) = row
+            .rsplit_once(':')
+            .ok_or_else(|| ConstraintError::Parse(format!("Row '{}' is not in guess:states form", row)))?;
+        if guess.chars().count() != states.chars().count() {
+            return Err(ConstraintError::Parse(format!("Row '{}' has {} characters but {} states", row, guess.chars().count(), states.chars().count())));
+        }
+
+        guess
+            .chars()
+            .zip(states.chars())
+            .map(|(c, s)| {
+                let state = match s.to_ascii_uppercase() {
+                    'G' => "correct",
+                    'Y' => "present",
+                    'B' | '.' => "empty",
+                    _ => return Err(ConstraintError::Parse(format!("Unknown tile state '{}' in row '{}'", s, row))),
+                };
+                Ok(Tile { char: c.to_string(), state: state.to_string() })
+            })
+            .collect()
+    }
+}
+
+// Reasons why a set of guess rows cannot be turned into search constraints
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstraintError {
+    Parse(String),
+    FixedConflict { position: usize, first: char, second: char },
+    ExactCountConflict { ch: char, first: usize, second: usize },
+    ExactBelowMinimum { ch: char, exact: usize, min: usize },
+    FixedButForbidden { ch: char, position: usize },
+    FixedButExcluded { ch: char, position: usize },
+    FixedAboveExact { ch: char, exact: usize },
+    ForbiddenButRequired { ch: char },
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::Parse(e) => write!(f, "Failed to parse constraints: {}", e),
+            ConstraintError::FixedConflict { position, first, second } =>
+                write!(f, "Conflict: Position {} is fixed to both {} and {}", position, first, second),
+            ConstraintError::ExactCountConflict { ch, first, second } =>
+                write!(f, "Conflict: Character '{}' has different exact counts in different rows ({} vs {})", ch, first, second),
+            ConstraintError::ExactBelowMinimum { ch, exact, min } =>
+                write!(f, "Conflict: Character '{}' exact count ({}) is less than minimum required ({})", ch, exact, min),
+            ConstraintError::FixedButForbidden { ch, position } =>
+                write!(f, "Conflict: Character '{}' is fixed at position {} but also globally forbidden", ch, position),
+            ConstraintError::FixedButExcluded { ch, position } =>
+                write!(f, "Conflict: Character '{}' is fixed at position {} but also marked as cannot be at that position", ch, position),
+            ConstraintError::FixedAboveExact { ch, exact } =>
+                write!(f, "Conflict: Character '{}' exact count ({}) is less than minimum fixed requirement", ch, exact),
+            ConstraintError::ForbiddenButRequired { ch } =>
+                write!(f, "Conflict: Character '{}' is globally forbidden but also required to appear", ch),
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+#[derive(Clone)]
+pub struct SumzleSolver {
+    length: usize,
+    // Symbols the search may place; levels without e.g. `A` or `[]` leave them out
+    allowed_symbols: CharSet,
+    max_operand_value: i32,
+    rules: RuleSet,
+    cache: SearchCache,
+    dictionary: Option<Arc<Dictionary>>,
+}
+
+// Result-returning evaluation API
+impl SumzleSolver {
+    pub fn with_rules(length: usize, max_operand_value: i32, rules: RuleSet) -> Self {
+        let mut solver = Self::new(length, max_operand_value);
+        solver.rules = rules;
+        solver
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    // Restrict the search to `allowed_symbols`, for levels with a reduced
+    // operator set. Equations are still validated with the full grammar.
+    pub fn with_symbols(length: usize, max_operand_value: i32, allowed_symbols: CharSet) -> Self {
+        let mut solver = Self::new(length, max_operand_value);
+        solver.allowed_symbols = allowed_symbols;
+        solver
+    }
+
+    pub fn allowed_symbols(&self) -> CharSet {
+        self.allowed_symbols
+    }
+
+    pub fn max_operand_value(&self) -> i32 {
+        self.max_operand_value
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+        self.cache.clear();
+    }
+
+    pub(crate) fn grammar(&self) -> Grammar {
+        Grammar { permutation_before_factorial: self.rules.game_operand_rules }
+    }
+
+    pub fn try_evaluate_expression(&self, expr: &str) -> Result<i32, EvalError> {
+        let ast = parse_expression_with(expr, self.grammar())?;
+        let value = self.to_integer(self.evaluate_ast(&ast)?)?;
+        i32::try_from(value).map_err(|_| EvalError::Overflow)
+    }
+
+    pub fn validate_equation(&self, expression: &str) -> Result<(), EvalError> {
+        let equation = parse_equation_with(expression, self.grammar())?;
+
+        // Evaluate both sides
+        let left = self.evaluate_ast(&equation.lhs)?;
+        let right = self.evaluate_ast(&equation.rhs)?;
+        if self.rules.js_numbers {
+            return Self::validate_js(left.as_f64(), equation.relation, right.as_f64());
+        }
+        let left_value = self.to_integer(left)?;
+        let right_value = self.to_integer(right)?;
+
+        // Check if the equation is valid
+        let satisfied = match equation.relation {
+            Relation::Eq => left_value == right_value,
+            Relation::Gt => left_value > right_value,
+        };
+        if satisfied { Ok(()) } else { Err(EvalError::NotSatisfied) }
+    }
+
+    // The checks of the game's `isValidEquation`, in its order
+    fn validate_js(left: f64, relation: Relation, right: f64) -> Result<(), EvalError> {
+        if left.is_nan() || right.is_nan() {
+            return Err(EvalError::InvalidResult);
+        }
+        let satisfied = match relation {
+            Relation::Eq => (left - right).abs() < JS_TOLERANCE,
+            Relation::Gt => left > right,
+        };
+        if !satisfied {
+            return Err(EvalError::NotSatisfied);
+        }
+        Self::check_js_integer(left)?;
+        Self::check_js_integer(right)
+    }
+
+    // A side counts as an integer within 0.0001. Infinity passes, since the
+    // game's check compares NaN there.
+    fn check_js_integer(value: f64) -> Result<(), EvalError> {
+        if value.is_nan() {
+            return Err(EvalError::InvalidResult);
+        }
+        if (value - value.round()).abs() > JS_TOLERANCE { Err(EvalError::NonInteger) } else { Ok(()) }
+    }
+
+    // Evaluate a parsed expression tree, exactly or with JS numbers as the
+    // rule set says. Exact intermediate results may be fractions; overflow is
+    // rejected instead of rounded.
+    fn evaluate_ast(&self, expr: &Expr) -> Result<Value, EvalError> {
+        let game = self.rules.game_operand_rules;
+        match expr {
+            Expr::Num(n) => Ok(Value::integer(*n as i128, self.rules.js_numbers)),
+            Expr::Neg(inner) => {
+                if game && matches!(**inner, Expr::Neg(_) | Expr::BinOp(BinOp::Pow, _, _)) {
+                    return Err(EvalError::Syntax);
+                }
+                self.evaluate_ast(inner)?.checked_neg()
+            },
+            Expr::Paren(inner) => self.evaluate_ast(inner),
+            Expr::Floor(inner) => {
+                if game && Self::contains_floor(inner) {
+                    return Err(EvalError::Syntax);
+                }
+                Ok(self.floor(self.evaluate_ast(inner)?))
+            },
+            Expr::Factorial(inner) => {
+                if game && matches!(**inner, Expr::Factorial(_)) {
+                    return Err(EvalError::Syntax);
+                }
+                self.factorial(self.evaluate_ast(inner)?)
+            },
+            Expr::Perm(m, n) => {
+                if game && !(Self::is_perm_operand(m) && Self::is_perm_operand(n)) {
+                    return Err(EvalError::Syntax);
+                }
+                self.permutation(self.evaluate_ast(m)?, self.evaluate_ast(n)?)
+            },
+            Expr::BinOp(op, lhs, rhs) => {
+                if game && *op == BinOp::Sub && matches!(**rhs, Expr::Neg(_)) {
+                    return Err(EvalError::Syntax); // `--` is a decrement in JS
+                }
+                self.binary(*op, self.evaluate_ast(lhs)?, self.evaluate_ast(rhs)?)
+            },
+        }
+    }
+
+    // The operators on evaluated operands, shared by `evaluate_ast` and the
+    // search's `PrefixEvaluation`
+
+    pub(crate) fn floor(&self, value: Value) -> Value {
+        match self.rules.floor_rounding {
+            FloorRounding::Floor => value.floor(),
+            FloorRounding::Truncate => value.trunc(),
+        }
+    }
+
+    pub(crate) fn factorial(&self, value: Value) -> Result<Value, EvalError> {
+        let n = value.to_integer().ok_or(EvalError::FactorialOutOfRange)?;
+        if !(0..=self.rules.max_factorial).contains(&n) {
+            return Err(EvalError::FactorialOutOfRange);
+        }
+        // Exact in doubles too, up to the game's 20!
+        Ok(Value::integer((2..=n).product(), self.rules.js_numbers))
+    }
+
+    pub(crate) fn permutation(&self, m: Value, n: Value) -> Result<Value, EvalError> {
+        let m = m.to_integer().ok_or(EvalError::InvalidPermutation)?;
+        let n = n.to_integer().ok_or(EvalError::InvalidPermutation)?;
+        if self.rules.game_operand_rules && n == 0 && m >= 0 {
+            return Ok(Value::integer(1, self.rules.js_numbers));
+        }
+        if !(0..=10).contains(&m) || !(0..=m).contains(&n) {
+            return Err(EvalError::InvalidPermutation);
+        }
+        Ok(Value::integer((0..n).map(|i| m - i).product(), self.rules.js_numbers))
+    }
+
+    pub(crate) fn binary(&self, op: BinOp, a: Value, b: Value) -> Result<Value, EvalError> {
+        let game = self.rules.game_operand_rules;
+        match op {
+            BinOp::Add => a.checked_add(b),
+            BinOp::Sub => a.checked_sub(b),
+            BinOp::Mul => a.checked_mul(b),
+            BinOp::Div => a.checked_div(b),
+            // The game's `checkModuloOperation`
+            BinOp::Mod if game && b.is_zero() => Err(EvalError::DivisionByZero),
+            BinOp::Mod if game && !b.is_integer() => Err(EvalError::NonInteger),
+            BinOp::Mod => a.checked_rem(b),
+            BinOp::Pow => a.checked_pow(b),
+        }
+    }
+
+    fn is_perm_operand(expr: &Expr) -> bool {
+        matches!(expr, Expr::Num(_) | Expr::Paren(_))
+    }
+
+    fn contains_floor(expr: &Expr) -> bool {
+        match expr {
+            Expr::Num(_) => false,
+            Expr::Floor(_) => true,
+            Expr::Neg(e) | Expr::Paren(e) | Expr::Factorial(e) => Self::contains_floor(e),
+            Expr::BinOp(_, a, b) | Expr::Perm(a, b) => Self::contains_floor(a) || Self::contains_floor(b),
+        }
+    }
+
+    // The integer a side evaluates to. Exact values must be integers within
+    // the rule set's range; JS numbers are rounded if the game would take
+    // them as integers, and must be finite.
+    fn to_integer(&self, value: Value) -> Result<i128, EvalError> {
+        let n = match value {
+            Value::Exact(r) => r.to_integer().ok_or(EvalError::NonInteger)?,
+            Value::Double(v) => {
+                Self::check_js_integer(v)?;
+                if v.abs() >= i128::MAX as f64 {
+                    return Err(EvalError::Overflow);
+                }
+                v.round() as i128
+            },
+        };
+        if n.unsigned_abs() > self.rules.max_abs_value.unsigned_abs() {
+            return Err(EvalError::Overflow);
+        }
+        Ok(n)
+    }
+}
+
+impl SumzleSolver {
+    pub fn new(length: usize, max_operand_value: i32) -> Self {
+        Self {
+            length,
+            allowed_symbols: CharSet::ALL,
+            max_operand_value,
+            rules: RuleSet::classic(),
+            cache: SearchCache::default(),
+            dictionary: None,
+        }
+    }
+
+    // Switch between the "classic" solver rules and the "game" compatible ones
+    pub fn set_rule_profile(&mut self, profile: &str) -> bool {
+        match RuleSet::from_name(profile) {
+            Some(rules) => {
+                self.set_rules(rules);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn evaluate_expression(&self, expr: &str) -> Option<i32> {
+        self.try_evaluate_expression(expr).ok()
+    }
+
+    pub fn is_valid_solution(&self, expression: &str) -> bool {
+        self.is_valid_equation(expression)
+    }
+
+    fn is_valid_equation(&self, expression: &str) -> bool {
+        self.validate_equation(expression).is_ok()
+    }
+
+    // Helper functions for the search algorithm
+    fn is_digit(&self, c: char) -> bool {
+        c.is_ascii_digit()
+    }
+
+    fn is_binary_operator(&self, c: char) -> bool {
+        matches!(c, '+' | '-' | '*' | '/' | '%' | '^' | 'A')
+    }
+
+    fn is_unary_post_operator(&self, c: char) -> bool {
+        c == '!'
+    }
+
+    fn is_operator(&self, c: char) -> bool {
+        self.is_binary_operator(c) || self.is_unary_post_operator(c)
+    }
+
+    fn is_open_bracket(&self, c: char) -> bool {
+        c == '(' || c == '['
+    }
+
+    fn is_close_bracket(&self, c: char) -> bool {
+        c == ')' || c == ']'
+    }
+
+    pub(crate) fn is_main_operator(&self, c: char) -> bool {
+        c == '=' || c == '>'
+    }
+
+    fn get_matching_bracket(&self, open_bracket: char) -> Option<char> {
+        match open_bracket {
+            '(' => Some(')'),
+            '[' => Some(']'),
+            _ => None,
+        }
+    }
+
+    // Preprocess constraints to initialize the GlobalKnowledge object
+    pub(crate) fn preprocess_constraints(&self, constraints: &Constraints) -> Result<GlobalKnowledge, ConstraintError> {
+
+        // Initialize GlobalKnowledge
+        let mut gk = GlobalKnowledge {
+            fixed_chars: vec![None; self.length],
+            cannot_be_at: vec![CharSet::EMPTY; self.length],
+            globally_forbidden: CharSet::EMPTY,
+            exact_counts: [None; SYMBOL_COUNT],
+            required_counts: Vec::new(),
+            expression_ranges: expression_ranges(self.length, self.max_operand_value, &self.rules),
+            fixed_after: vec![CharCounts::default(); self.length],
+            free_after: vec![0; self.length],
+        };
+        let mut must_appear_min_count: HashMap<char, usize> = HashMap::new();
+        let mut must_appear_exact_count: HashMap<char, usize> = HashMap::new();
+        let mut globally_forbidden: HashSet<char> = HashSet::new();
+
+        // Process each row of constraints
+        for row in &constraints.rows {
+            for (c, tile) in row.iter().enumerate() {
+                if c >= self.length || tile.char.is_empty() {
+                    continue;
+                }
+
+                let tile_char = tile.char.chars().next().unwrap();
+
+                match tile.state.as_str() {
+                    "correct" => {
+                        if let Some(fixed) = gk.fixed_chars[c] {
+                            if fixed != tile_char {
+                                return Err(ConstraintError::FixedConflict { position: c + 1, first: fixed, second: tile_char });
+                            }
+                        }
+                        gk.fixed_chars[c] = Some(tile_char);
+                        for vc in self.allowed_symbols.iter() {
+                            if vc != tile_char {
+                                gk.cannot_be_at[c].insert(vc);
+                            }
+                        }
+                    },
+                    "present" => {
+                        gk.cannot_be_at[c].insert(tile_char);
+                    },
+                    "empty" | "absent" => {
+                        gk.cannot_be_at[c].insert(tile_char);
+                    },
+                    _ => {}
+                }
+            }
+        }
+
+        // Collect all characters in guesses
+        let mut all_chars_in_guesses = HashSet::new();
+        for row in &constraints.rows {
+            for tile in row {
+                if !tile.char.is_empty() {
+                    all_chars_in_guesses.insert(tile.char.chars().next().unwrap());
+                }
+            }
+        }
+
+        // Process character counts
+        for &char in &all_chars_in_guesses {
+            let mut min_required_overall = 0;
+            let mut derived_exact_count = None;
+
+            for row in &constraints.rows {
+                if !row.iter().any(|tile| !tile.char.is_empty() && tile.char.chars().next().unwrap() == char) {
+                    continue;
+                }
+
+                let mut green_in_row = 0;
+                let mut yellow_in_row = 0;
+
+                for tile in row {
+                    if !tile.char.is_empty() && tile.char.chars().next().unwrap() == char {
+                        match tile.state.as_str() {
+                            "correct" => green_in_row += 1,
+                            "present" => yellow_in_row += 1,
+                            _ => {}
+                        }
+                    }
+                }
+
+                let min_required_this_row = green_in_row + yellow_in_row;
+                min_required_overall = min_required_overall.max(min_required_this_row);
+
+                if row.iter().any(|tile| !tile.char.is_empty() && tile.char.chars().next().unwrap() == char && (tile.state == "empty" || tile.state == "absent")) {
+                    let exact_count_this_row = green_in_row + yellow_in_row;
+                    if let Some(count) = derived_exact_count {
+                        if count != exact_count_this_row {
+                            return Err(ConstraintError::ExactCountConflict { ch: char, first: count, second: exact_count_this_row });
+                        }
+                    } else {
+                        derived_exact_count = Some(exact_count_this_row);
+                    }
+                }
+            }
+
+            must_appear_min_count.insert(char, min_required_overall);
+
+            if let Some(exact_count) = derived_exact_count {
+                if exact_count < min_required_overall {
+                    return Err(ConstraintError::ExactBelowMinimum { ch: char, exact: exact_count, min: min_required_overall });
+                }
+                must_appear_exact_count.insert(char, exact_count);
+                if exact_count == 0 && min_required_overall == 0 {
+                    globally_forbidden.insert(char);
+                }
+            }
+        }
+
+        // Check for conflicts
+        for i in 0..self.length {
+            if let Some(fixed) = gk.fixed_chars[i] {
+                if globally_forbidden.contains(&fixed) {
+                    return Err(ConstraintError::FixedButForbidden { ch: fixed, position: i + 1 });
+                }
+                if gk.cannot_be_at[i].contains(fixed) {
+                    return Err(ConstraintError::FixedButExcluded { ch: fixed, position: i + 1 });
+                }
+                let min_count = *must_appear_min_count.get(&fixed).unwrap_or(&0);
+                must_appear_min_count.insert(fixed, min_count.max(1));
+                if let Some(&exact_count) = must_appear_exact_count.get(&fixed) {
+                    if exact_count < *must_appear_min_count.get(&fixed).unwrap_or(&0) {
+                        return Err(ConstraintError::FixedAboveExact { ch: fixed, exact: exact_count });
+                    }
+                }
+            }
+        }
+
+        for (char, &exact) in &must_appear_exact_count {
+            let min = *must_appear_min_count.get(char).unwrap_or(&0);
+            if exact < min {
+                return Err(ConstraintError::ExactBelowMinimum { ch: *char, exact, min });
+            }
+        }
+
+        for &char in &globally_forbidden {
+            if *must_appear_min_count.get(&char).unwrap_or(&0) > 0 {
+                return Err(ConstraintError::ForbiddenButRequired { ch: char });
+            }
+            if let Some(&count) = must_appear_exact_count.get(&char) {
+                if count > 0 {
+                    return Err(ConstraintError::ForbiddenButRequired { ch: char });
+                }
+            }
+        }
+
+        // Dense tables for the search
+        for &char in &globally_forbidden {
+            gk.globally_forbidden.insert(char);
+        }
+        for (&char, &exact) in &must_appear_exact_count {
+            if let Some(i) = symbol_index(char) {
+                gk.exact_counts[i] = Some(exact.min(u8::MAX as usize) as u8);
+            }
+        }
+        for (&char, &min_count) in &must_appear_min_count {
+            let required = *must_appear_exact_count.get(&char).unwrap_or(&min_count);
+            if required > 0 {
+                gk.required_counts.push((char, required));
+            }
+        }
+        gk.required_counts.sort_unstable();
+        for i in (0..self.length.saturating_sub(1)).rev() {
+            gk.fixed_after[i] = gk.fixed_after[i + 1];
+            gk.free_after[i] = gk.free_after[i + 1];
+            match gk.fixed_chars[i + 1] {
+                Some(c) => gk.fixed_after[i].add(c),
+                None => gk.free_after[i] += 1,
+            }
+        }
+
+        Ok(gk)
+    }
+
+    // Check if a character can be placed at a given position
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn can_place_char(&self, 
+                                 char: char, 
+                                 index: usize, 
+                                 current_expression: &[char], 
+                                 main_op_so_far: Option<char>, 
+                                 current_expression_counts: &CharCounts, 
+                                 open_brackets: &[char],
+                                 floor_context: &FloorContext,
+                                 gk: &GlobalKnowledge) -> bool {
+        // Check global constraints
+        if !self.allowed_symbols.contains(char) || gk.globally_forbidden.contains(char) {
+            return false;
+        }
+        if let Some(fixed) = gk.fixed_chars[index] {
+            if fixed != char {
+                return false;
+            }
+        }
+        if gk.cannot_be_at[index].contains(char) {
+            return false;
+        }
+
+        // Check character count constraints
+        let current_count = current_expression_counts.get(char);
+        if let Some(exact_count) = gk.exact_count(char) {
+            if current_count >= exact_count {
+                return false;
+            }
+        }
+
+        if !self.counts_remain_feasible(char, index, current_expression_counts, gk) {
+            return false;
+        }
+
+        // Check floor context constraints
+        if floor_context.in_floor && !self.rules.floor_division_only {
+            if char == '[' || self.is_main_operator(char) {
+                return false;
+            }
+            if char == ']' && index > 0 {
+                let prev_char = current_expression[index - 1];
+                if !self.is_digit(prev_char) && prev_char != ')' && prev_char != '!' {
+                    return false;
+                }
+            }
+        } else if floor_context.in_floor {
+            if char == '[' {
+                return false;
+            }
+            if self.is_operator(char) && char != '/' {
+                return false;
+            }
+            if self.is_main_operator(char) {
+                return false;
+            }
+            if char == '(' {
+                return false;
+            }
+            if char == 'A' || char == '!' {
+                return false;
+            }
+
+            if char == '/' {
+                if floor_context.has_slash_in_current_floor {
+                    return false;
+                }
+                let prev_char = if index > 0 { current_expression[index - 1] } else { '\0' };
+                if !self.is_digit(prev_char) || index == 0 {
+                    return false;
+                }
+            } else if char == ']' {
+                let prev_char = if index > 0 { current_expression[index - 1] } else { '\0' };
+                if !self.is_digit(prev_char) {
+                    return false;
+                }
+                if !floor_context.has_slash_in_current_floor {
+                    return false;
+                }
+            } else if !self.is_digit(char) {
+                return false;
+            }
+        }
+
+        // Check bracket context constraints
+        if char == '[' && floor_context.in_floor {
+            return false;
+        }
+        if char == ']' && !floor_context.in_floor {
+            return false;
+        }
+        if char == '[' && index >= self.length.saturating_sub(3) {
+            return false;
+        }
+
+        // Check number constraints
+        if self.is_digit(char) && main_op_so_far != Some('=') {
+            let start = current_expression[..index].iter().rposition(|&c| !self.is_digit(c)).map_or(0, |k| k + 1);
+            let digits = &current_expression[start..index];
+
+            if digits.first() == Some(&'0') {
+                return false;
+            }
+
+            let char_before_number_sequence = start.checked_sub(1).map(|k| current_expression[k]);
+            if char_before_number_sequence.is_none_or(|c| self.is_operator(c) || self.is_open_bracket(c) || self.is_main_operator(c)) {
+                let number = digits
+                    .iter()
+                    .chain([&char])
+                    .try_fold(0i64, |n, c| n.checked_mul(10)?.checked_add(c.to_digit(10)? as i64));
+                if number.is_none_or(|n| n > self.max_operand_value as i64) {
+                    return false;
+                }
+            }
+        }
+
+        // Check syntax constraints
+        let prev_char = if index > 0 { Some(current_expression[index - 1]) } else { None };
+
+        if index == 0 && (self.is_binary_operator(char) || self.is_close_bracket(char) || self.is_main_operator(char) || self.is_unary_post_operator(char)) {
+            return false;
+        }
+
+        if let Some(prev) = prev_char {
+            if self.is_digit(prev) {
+                if self.is_open_bracket(char) && char != '[' {
+                    return false;
+                }
+                if char == '[' && floor_context.in_floor {
+                    return false;
+                }
+            } else if self.is_operator(prev) {
+                if self.is_binary_operator(char) && !(prev == 'A' && (self.is_open_bracket(char) || self.is_digit(char))) && !self.is_unary_post_operator(prev) {
+                    return false;
+                }
+                if self.is_close_bracket(char) {
+                    return false;
+                }
+                if self.is_main_operator(char) && !self.is_unary_post_operator(prev) {
+                    return false;
+                }
+                if self.is_unary_post_operator(prev) && (self.is_digit(char) || self.is_open_bracket(char)) {
+                    return false;
+                }
+            } else if self.is_open_bracket(prev) {
+                if prev == '[' && char == '(' && self.rules.floor_division_only {
+                    return false;
+                }
+                if self.is_binary_operator(char) {
+                    return false;
+                }
+                if self.is_close_bracket(char) && self.get_matching_bracket(prev) != Some(char) {
+                    return false;
+                }
+                if self.is_main_operator(char) {
+                    return false;
+                }
+                if self.is_unary_post_operator(char) {
+                    return false;
+                }
+            } else if self.is_close_bracket(prev) {
+                if self.is_digit(char) {
+                    return false;
+                }
+                if self.is_open_bracket(char) {
+                    return false;
+                }
+            } else if self.is_main_operator(prev) {
+                if prev == '=' {
+                    if !self.is_digit(char) && char != '-' {
+                        return false;
+                    }
+                } else {
+                    if self.is_main_operator(char) {
+                        return false;
+                    }
+                    if self.is_close_bracket(char) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        if main_op_so_far == Some('=') {
+            if !self.is_digit(char) && char != '-' {
+                return false;
+            }
+            if char == '-' && prev_char == Some('=') && index >= self.length - 1 {
+                return false; // - at the very end like ...=-
+            }
+        }
+
+        if index == self.length - 1 && (self.is_binary_operator(char) || self.is_open_bracket(char) || self.is_main_operator(char)) {
+            return false;
+        }
+
+        // Check bracket matching against the brackets still open before this position
+        if self.is_close_bracket(char) && open_brackets.last().and_then(|&open| self.get_matching_bracket(open)) != Some(char) {
+            return false;
+        }
+
+        let open_after = open_brackets.len() + usize::from(self.is_open_bracket(char)) - usize::from(self.is_close_bracket(char));
+        if index == self.length - 1 && open_after != 0 {
+            return false;
+        }
+
+        // Check main operator constraints
+        if self.is_main_operator(char) {
+            if let Some(main_op) = main_op_so_far {
+                if main_op != char && !(main_op == '>' && char == '=') {
+                    return false;
+                }
+                if main_op == char && char == '=' {
+                    return false;
+                }
+            }
+            if index == 0 || index >= self.length - 1 {
+                return false;
+            }
+        }
+
+        // Check special character constraints
+        if char == 'A' && prev_char.is_none_or(|c| !self.is_digit(c) && !self.is_close_bracket(c)) {
+            return false;
+        }
+
+        if prev_char == Some('A') && !self.is_digit(char) && !self.is_open_bracket(char) {
+            return false;
+        }
+
+        if char == '!' {
+            if prev_char.is_none() {
+                return false;
+            }
+            if let Some(prev) = prev_char {
+                if self.is_digit(prev) {
+                    if prev == '0' && self.factorial(Value::integer(0, self.rules.js_numbers)).is_err() {
+                        return false;
+                    }
+                } else if self.is_close_bracket(prev) {
+                    if prev == ']' && self.rules.floor_division_only {
+                        return false;
+                    }
+                } else {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    // After placing `char` at `index`, the free positions left must be able to
+    // hold every required character still missing. Fixed positions further
+    // on supply their own character and are not free.
+    fn counts_remain_feasible(&self, char: char, index: usize, current_expression_counts: &CharCounts, gk: &GlobalKnowledge) -> bool {
+        let mut missing = 0;
+        for &(c, required) in &gk.required_counts {
+            let placed = current_expression_counts.get(c) + usize::from(c == char);
+            let fixed_ahead = gk.fixed_after[index].get(c);
+            missing += required.saturating_sub(placed + fixed_ahead);
+        }
+        missing <= gk.free_after[index]
+    }
+
+    // Extend the running evaluation of the expression by `char` at `index`.
+    // Each operand is evaluated as soon as a character completes it, and if
+    // that fails (division by zero, overflow, a factorial out of range, ...)
+    // so does every completion. A relation completes the left side, which
+    // must be an integer in range, and for '>' greater than the smallest
+    // value the remaining positions can express. A top-level `+`/`-` of the
+    // left side is checked against the widths left for the rest of the
+    // equation.
+    pub(crate) fn advance_evaluation(&self, evaluation: &mut PrefixEvaluation, char: char, index: usize, gk: &GlobalKnowledge) -> bool {
+        match evaluation.push(char, self) {
+            Ok(None) => true,
+            Ok(Some(lhs)) if self.is_main_operator(char) => self.left_side_is_valid(lhs, char, index, gk),
+            Ok(Some(sum)) => self.left_side_can_balance(sum.as_f64(), char, index, gk),
+            Err(_) => false,
+        }
+    }
+
+    fn left_side_is_valid(&self, value: Value, relation: char, index: usize, gk: &GlobalKnowledge) -> bool {
+        let lhs = match value {
+            // An infinite side still counts as an integer in the game
+            Value::Double(v) if relation == '>' => match Self::check_js_integer(v) {
+                Ok(()) => v,
+                Err(_) => return false,
+            },
+            _ => match self.to_integer(value) {
+                Ok(n) => n as f64,
+                Err(_) => return false,
+            },
+        };
+        if relation == '>' {
+            return match gk.expression_ranges[self.length - index - 1] {
+                Some(rhs) => lhs > rhs.lo,
+                None => false,
+            };
+        }
+        true
+    }
+
+    // A binary `+`/`-` at the top level of the left side splits it into the
+    // finished `sum` before it and an expression after it. Wherever the
+    // relation then goes, that expression's width and the right-hand side's
+    // are known, and the left side must be able to equal a literal of the
+    // one or exceed an expression of the other.
+    fn left_side_can_balance(&self, sum: f64, char: char, index: usize, gk: &GlobalKnowledge) -> bool {
+        if !sum.is_finite() {
+            return true;
+        }
+        (index + 2..self.length.saturating_sub(1)).any(|relation_index| {
+            let Some(term) = gk.expression_ranges[relation_index - index - 1] else {
+                return false;
+            };
+            let lhs = match char {
+                '+' => Interval::new(sum + term.lo, sum + term.hi),
+                _ => Interval::new(sum - term.hi, sum - term.lo),
+            }.widened();
+            let width = self.length - relation_index - 1;
+            let can_place = |relation: char| {
+                self.allowed_symbols.contains(relation)
+                    && !gk.globally_forbidden.contains(relation)
+                    && !gk.cannot_be_at[relation_index].contains(relation)
+                    && gk.fixed_chars[relation_index].is_none_or(|fixed| fixed == relation)
+            };
+            // A literal of `width` characters, a minus sign counting as one
+            let literal_lo = if width == 1 { 0.0 } else { 1.0 - 10f64.powi(width as i32 - 1) };
+            let literal_hi = 10f64.powi(width as i32) - 1.0;
+            (can_place('=') && lhs.hi >= literal_lo && lhs.lo <= literal_hi)
+                || (can_place('>') && gk.expression_ranges[width].is_some_and(|rhs| lhs.hi > rhs.lo))
+        })
+    }
+
+    // Get the optimized order of characters to try at a given position
+    pub(crate) fn get_optimized_char_order(&self, 
+                                          index: usize, 
+                                          current_expression: &[char], 
+                                          main_op_so_far: Option<char>, 
+                                          floor_context: &FloorContext,
+                                          gk: &GlobalKnowledge) -> Vec<char> {
+        if let Some(fixed) = gk.fixed_chars[index] {
+            return vec![fixed];
+        }
+
+        let mut ordered_chars = Vec::new();
+        let prev_char = if index > 0 { current_expression[index - 1] } else { '\0' };
+
+        if floor_context.in_floor && self.rules.floor_division_only {
+            if floor_context.has_slash_in_current_floor {
+                ordered_chars.extend("0123456789]".chars());
+            } else {
+                ordered_chars.extend("0123456789/".chars());
+            }
+        } else if main_op_so_far == Some('=') {
+            if prev_char == '=' {
+                ordered_chars.extend("-0123456789".chars());
+            } else {
+                ordered_chars.extend("0123456789".chars());
+            }
+        } else if index == 0 {
+            ordered_chars.extend("1234567890([".chars());
+        } else if self.is_digit(prev_char) {
+            ordered_chars.extend("0123456789+-*/%^A!)]=>[".chars());
+        } else if prev_char == '>' {
+            // The right-hand side may start with a sign
+            ordered_chars.extend("1234567890([-+".chars());
+        } else if self.is_binary_operator(prev_char) || self.is_open_bracket(prev_char) {
+            ordered_chars.extend("1234567890([".chars());
+        } else if self.is_close_bracket(prev_char) || self.is_unary_post_operator(prev_char) {
+            ordered_chars.extend("+-*/%^A!)]=>[".chars());
+        } else {
+            ordered_chars.extend("1234567890+-*/=()[]%^!A>".chars());
+        }
+
+        if index == self.length - 1 && !floor_context.in_floor {
+            let end_chars: Vec<char> = "0123456789)]!".chars().collect();
+            ordered_chars.retain(|c| end_chars.contains(c));
+            if ordered_chars.is_empty() && prev_char != '\0' {
+                ordered_chars = end_chars;
+            } else if ordered_chars.is_empty() && index == 0 && self.length == 1 {
+                ordered_chars.extend("0123456789".chars());
+            }
+        }
+
+        // Remove duplicates and filter by constraints
+        let mut unique_chars = Vec::new();
+        for &c in ordered_chars.iter() {
+            if !unique_chars.contains(&c) && 
+               self.allowed_symbols.contains(c) &&
+               !gk.globally_forbidden.contains(c) && 
+               !gk.cannot_be_at[index].contains(c) {
+                unique_chars.push(c);
+            }
+        }
+
+        unique_chars
+    }
+
+    // The right-hand side after '=' is an integer literal, so once the left
+    // side is complete it is determined: the value written out, or "-0" for
+    // zero, whichever takes exactly `width` characters
+    pub(crate) fn right_hand_side(&self, lhs: Value, width: usize) -> Option<Vec<char>> {
+        let value = self.to_integer(lhs).ok()?;
+
+        let mut rhs = value.to_string();
+        if value == 0 && width == 2 {
+            rhs = "-0".to_string();
+        }
+        if rhs.len() == width { Some(rhs.chars().collect()) } else { None }
+    }
+
+    // Final check of a completed expression: a main operator, the character
+    // counts the constraints demand, and the equation itself
+    pub(crate) fn accept_complete(&self,
+                                  current_expression: &[char],
+                                  main_op_so_far: Option<char>,
+                                  current_expression_counts: &CharCounts,
+                                  gk: &GlobalKnowledge) -> Option<String> {
+        // Check if the expression has a main operator
+        main_op_so_far?;
+
+        // Check character count constraints
+        if !gk.counts_satisfied(current_expression_counts) {
+            return None;
+        }
+
+        // Check if the expression is a valid solution
+        let expr_str: String = current_expression.iter().collect();
+        if self.is_valid_solution(&expr_str) { Some(expr_str) } else { None }
+    }
+
+    // Implementation of the search algorithm
+    pub fn search(&self, constraints: &Constraints) -> Result<Vec<String>, ConstraintError> {
+        self.search_counted(constraints).map(|(results, _)| results)
+    }
+
+    pub fn search_json(&self, constraints_json: &str) -> Result<Vec<String>, ConstraintError> {
+        self.search(&Constraints::from_json(constraints_json)?)
+    }
+
+    // Rank next guesses by the feedback partition they induce over the
+    // current candidates: by expected entropy, or by the worst-case bucket
+    // size for `RecommendMode::Minimax`. With `include_non_candidates`, every
+    // valid equation of this length is considered as a guess too.
+    pub fn recommend_guess(&self, constraints: &Constraints, top_n: usize, include_non_candidates: bool, mode: RecommendMode) -> Result<Vec<GuessScore>, ConstraintError> {
+        let candidates = self.search(constraints)?;
+        if candidates.len() <= 1 || !include_non_candidates {
+            return Ok(rank_guesses(&candidates, &candidates, top_n, mode));
+        }
+        // Kept out of the cache, which holds the candidates of `constraints`
+        let (pool, _) = self.search_uncached(self.preprocess_constraints(&Constraints::default())?);
+        Ok(rank_guesses(&pool, &candidates, top_n, mode))
+    }
+
+    // Start a resumable search that can be advanced in bounded steps
+    pub fn start_search(&self, constraints: &Constraints) -> Result<SearchSession, ConstraintError> {
+        SearchSession::new(self, constraints)
+    }
+
+    // Search one of `shard_count` slices of the search tree. The two-character
+    // prefixes are split into contiguous ranges, so concatenating the shards
+    // in index order gives the same results as `search`.
+    pub fn search_shard(&self, constraints: &Constraints, shard_idx: usize, shard_count: usize) -> Result<StepResult, ConstraintError> {
+        let gk = self.preprocess_constraints(constraints)?;
+        let prefixes = SearchSession::prefixes_for(self, gk.clone(), 2);
+        let shard_count = shard_count.max(1);
+        let start = prefixes.len() * shard_idx.min(shard_count) / shard_count;
+        let end = prefixes.len() * (shard_idx + 1).min(shard_count) / shard_count;
+
+        let mut shard = StepResult { done: true, ..StepResult::default() };
+        for prefix in &prefixes[start..end] {
+            let part = SearchSession::from_knowledge(self, gk.clone(), prefix).step(usize::MAX);
+            shard.new_results.extend(part.new_results);
+            shard.searched += part.searched;
+        }
+        Ok(shard)
+    }
+
+    // Search returning the results and the number of complete expressions
+    // examined. When every row of the previous search is among this one's
+    // rows, its results are filtered instead of searching again; the count is
+    // then the number of cached results checked.
+    pub fn search_counted(&self, constraints: &Constraints) -> Result<(Vec<String>, usize), ConstraintError> {
+        let gk = self.preprocess_constraints(constraints)?;
+
+        let mut cache = self.cache.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(cached) = cache.as_mut() {
+            if cached.rows.iter().all(|row| constraints.rows.contains(row)) {
+                let checked = cached.results.len();
+                cached.results.retain(|candidate| gk.admits(candidate));
+                cached.rows = constraints.rows.clone();
+                return Ok((cached.results.clone(), checked));
+            }
+        }
+
+        let (results, searched_count) = self.search_uncached(gk);
+        *cache = Some(CachedSearch { rows: constraints.rows.clone(), results: results.clone() });
+        Ok((results, searched_count))
+    }
+
+    // Let `search` filter a precomputed dictionary instead of exploring the
+    // search tree. Results then come in dictionary (sorted) order. The
+    // dictionary is ignored while the rules differ from the ones it was built for.
+    pub fn load_dictionary(&mut self, dictionary: Dictionary) -> Result<(), DictionaryError> {
+        if !dictionary.matches(self) {
+            return Err(DictionaryError::Mismatch);
+        }
+        self.dictionary = Some(Arc::new(dictionary));
+        self.cache.clear();
+        Ok(())
+    }
+
+    fn search_uncached(&self, gk: GlobalKnowledge) -> (Vec<String>, usize) {
+        match &self.dictionary {
+            Some(dictionary) if dictionary.matches(self) => {
+                let results = dictionary
+                    .equations()
+                    .iter()
+                    .filter(|equation| equation.chars().all(|c| self.allowed_symbols.contains(c)) && gk.admits(equation))
+                    .cloned()
+                    .collect();
+                (results, dictionary.len())
+            },
+            _ => self.search_tree(gk),
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn search_tree(&self, gk: GlobalKnowledge) -> (Vec<String>, usize) {
+        let step = SearchSession::from_knowledge(self, gk, &[]).step(usize::MAX);
+        (step.new_results, step.searched)
+    }
+
+    // Parallel search: the subtrees below every two-character prefix are
+    // searched independently and concatenated in prefix order, which is the
+    // order the sequential search visits them in
+    #[cfg(feature = "parallel")]
+    fn search_tree(&self, gk: GlobalKnowledge) -> (Vec<String>, usize) {
+        use rayon::prelude::*;
+
+        let prefixes = SearchSession::prefixes_for(self, gk.clone(), 2);
+        let parts: Vec<StepResult> = prefixes
+            .par_iter()
+            .map(|prefix| SearchSession::from_knowledge(self, gk.clone(), prefix).step(usize::MAX))
+            .collect();
+
+        let mut results = Vec::new();
+        let mut searched_count = 0;
+        for part in parts {
+            results.extend(part.new_results);
+            searched_count += part.searched;
+        }
+        (results, searched_count)
+    }
+}
@@ -0,0 +1,117 @@
+// Values of evaluated expressions: exact rationals under the solver's own
+// rules, or JS numbers for rule sets reproducing the game's arithmetic
+
+use crate::error::EvalError;
+use crate::rational::{ArithmeticError, Rational};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Exact(Rational),
+    // An IEEE double as JS computes it: integers above 2^53 are rounded, and
+    // division by zero or a fractional power of a negative base gives
+    // Infinity or NaN instead of an error
+    Double(f64),
+}
+
+impl Value {
+    pub fn integer(n: i128, double: bool) -> Self {
+        if double { Value::Double(n as f64) } else { Value::Exact(Rational::from_integer(n)) }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Value::Exact(r) => r.numer() as f64 / r.denom() as f64,
+            Value::Double(v) => v,
+        }
+    }
+
+    // The value as an integer, if it is exactly one
+    pub fn to_integer(&self) -> Option<i128> {
+        match *self {
+            Value::Exact(r) => r.to_integer(),
+            Value::Double(v) if v.is_finite() && v.fract() == 0.0 && v.abs() < i128::MAX as f64 => Some(v as i128),
+            Value::Double(_) => None,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        match *self {
+            Value::Exact(r) => r.is_integer(),
+            Value::Double(v) => v.is_finite() && v.fract() == 0.0,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match *self {
+            Value::Exact(r) => r.is_zero(),
+            Value::Double(v) => v == 0.0,
+        }
+    }
+
+    fn binary(
+        self,
+        other: Self,
+        exact: fn(Rational, Rational) -> Result<Rational, ArithmeticError>,
+        double: fn(f64, f64) -> f64,
+    ) -> Result<Self, EvalError> {
+        match (self, other) {
+            (Value::Exact(a), Value::Exact(b)) => Ok(Value::Exact(exact(a, b)?)),
+            (a, b) => Ok(Value::Double(double(a.as_f64(), b.as_f64()))),
+        }
+    }
+
+    pub fn checked_neg(self) -> Result<Self, EvalError> {
+        match self {
+            Value::Exact(r) => Ok(Value::Exact(r.checked_neg()?)),
+            Value::Double(v) => Ok(Value::Double(-v)),
+        }
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, EvalError> {
+        self.binary(other, Rational::checked_add, |a, b| a + b)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, EvalError> {
+        self.binary(other, Rational::checked_sub, |a, b| a - b)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Result<Self, EvalError> {
+        self.binary(other, Rational::checked_mul, |a, b| a * b)
+    }
+
+    pub fn checked_div(self, other: Self) -> Result<Self, EvalError> {
+        self.binary(other, Rational::checked_div, |a, b| a / b)
+    }
+
+    // Remainder with the sign of the dividend; f64 `%` is JS's `%`
+    pub fn checked_rem(self, other: Self) -> Result<Self, EvalError> {
+        self.binary(other, Rational::checked_rem, |a, b| a % b)
+    }
+
+    pub fn checked_pow(self, other: Self) -> Result<Self, EvalError> {
+        self.binary(other, Rational::checked_pow, js_pow)
+    }
+
+    pub fn floor(self) -> Self {
+        match self {
+            Value::Exact(r) => Value::Exact(r.floor()),
+            Value::Double(v) => Value::Double(v.floor()),
+        }
+    }
+
+    pub fn trunc(self) -> Self {
+        match self {
+            Value::Exact(r) => Value::Exact(r.trunc()),
+            Value::Double(v) => Value::Double(v.trunc()),
+        }
+    }
+}
+
+// JS `**`, which differs from `powf` only where the exponent is NaN or
+// ±Infinity with a base of ±1
+fn js_pow(a: f64, b: f64) -> f64 {
+    if b.is_nan() || (b.is_infinite() && a.abs() == 1.0) {
+        return f64::NAN;
+    }
+    a.powf(b)
+}
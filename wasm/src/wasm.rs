@@ -0,0 +1,322 @@
+// wasm-bindgen bindings over the solver core
+
+use wasm_bindgen::prelude::*;
+use web_sys::console;
+use serde::Serialize;
+
+use crate::daily::{self, Date};
+use crate::dictionary::Dictionary;
+use crate::error::EvalErrorInfo;
+use crate::feedback::TileState;
+use crate::game::{GameRules, GameSession, GameStatus, Guess, SUDDEN_DEATH_SECONDS};
+use crate::grade::PuzzleGrader;
+use crate::recommend::RecommendMode;
+use crate::rules::{Difficulty, RuleSet};
+use crate::session::{SearchSession, StepResult};
+use crate::solver::{Constraints, SumzleSolver};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console)]
+    fn log(s: &str);
+}
+
+// Helper macro for logging to the console
+macro_rules! console_log {
+    ($($t:tt)*) => (log(&format!($($t)*)))
+}
+
+// Result of `evaluate` / `check_equation` as seen from JS
+#[derive(Serialize, Debug)]
+struct EvalOutcome {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<EvalErrorInfo>,
+}
+
+// Result of `GameSession.submit` as seen from JS
+#[derive(Serialize, Debug)]
+struct SubmitOutcome {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    feedback: Option<Vec<TileState>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<EvalErrorInfo>,
+    status: GameStatus,
+}
+
+// Everything the page needs to draw the board
+#[derive(Serialize, Debug)]
+struct GameView<'a> {
+    status: GameStatus,
+    rows: usize,
+    guesses: &'a [Guess],
+    hints_used: usize,
+    // Characters hints locked into the current row, by position
+    locked: Vec<Option<char>>,
+}
+
+// Target of the daily puzzle for an ISO `YYYY-MM-DD` date and a difficulty
+// ("easy", "medium" or "hard"); the same for every player on that day
+#[wasm_bindgen]
+pub fn daily_puzzle(date: &str, difficulty: &str) -> Result<String, JsValue> {
+    let date = Date::parse(date).ok_or_else(|| JsValue::from_str(&format!("Invalid date '{}'", date)))?;
+    let difficulty = Difficulty::from_name(difficulty).ok_or_else(|| JsValue::from_str(&format!("Unknown difficulty '{}'", difficulty)))?;
+    Ok(daily::daily_puzzle(date, difficulty).text)
+}
+
+// Rates targets by simulated play under the game's rules
+#[wasm_bindgen(js_name = PuzzleGrader)]
+pub struct WasmPuzzleGrader {
+    inner: PuzzleGrader,
+}
+
+#[wasm_bindgen(js_class = PuzzleGrader)]
+impl WasmPuzzleGrader {
+    #[wasm_bindgen(constructor)]
+    pub fn new(max_operand_value: i32) -> Self {
+        Self { inner: PuzzleGrader::new(max_operand_value, RuleSet::game_compatible()) }
+    }
+
+    // Use a dictionary written by `sumzle dict` for its length, so targets
+    // longer than 7 characters are rated on every equation, not a sample
+    pub fn add_dictionary(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        Dictionary::from_bytes(bytes)
+            .and_then(|dictionary| self.inner.add_dictionary(&dictionary))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // `{ optimal_guesses, expected_guesses, worst_case_guesses, stars }`, or
+    // null if the target is outside the search space
+    pub fn rate_difficulty(&mut self, target: &str) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.inner.rate_difficulty(target)).unwrap()
+    }
+}
+
+#[wasm_bindgen(js_name = GameSession)]
+pub struct WasmGameSession {
+    inner: GameSession,
+}
+
+#[wasm_bindgen(js_class = GameSession)]
+impl WasmGameSession {
+    // A game of `target` at "easy", "medium" or "hard", optionally with a
+    // single row or the 30 second sudden death timer
+    #[wasm_bindgen(constructor)]
+    pub fn new(target: &str, difficulty: &str, one_try: bool, sudden_death: bool) -> Result<WasmGameSession, JsValue> {
+        let difficulty = Difficulty::from_name(difficulty).ok_or_else(|| JsValue::from_str(&format!("Unknown difficulty '{}'", difficulty)))?;
+        let rules = GameRules {
+            difficulty,
+            one_try,
+            sudden_death_seconds: sudden_death.then_some(SUDDEN_DEATH_SECONDS),
+        };
+        Ok(Self { inner: GameSession::new(target, rules) })
+    }
+
+    // Seed the choice of hints, e.g. with `Math.random() * 2 ** 32`
+    pub fn set_seed(&mut self, seed: u32) {
+        self.inner.set_seed(seed as u64);
+    }
+
+    // `{ ok, feedback, status }` for an accepted guess, or
+    // `{ ok: false, error: { kind, message }, status }` for a rejected one
+    pub fn submit(&mut self, guess: &str) -> JsValue {
+        let outcome = match self.inner.submit(guess) {
+            Ok(feedback) => SubmitOutcome { ok: true, feedback: Some(feedback), error: None, status: self.inner.status() },
+            Err(e) => SubmitOutcome { ok: false, feedback: None, error: Some(e.to_info()), status: self.inner.status() },
+        };
+        serde_wasm_bindgen::to_value(&outcome).unwrap()
+    }
+
+    // `{ kind: "position", index, char }`, `{ kind: "symbol", char }`, or
+    // null if there is nothing to reveal; `input` is the current row as typed
+    pub fn hint(&mut self, input: &str) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.inner.hint(input)).unwrap()
+    }
+
+    // Call from the game timer with the seconds since the start; returns the status
+    pub fn tick(&mut self, elapsed_seconds: u32) -> String {
+        self.inner.tick(elapsed_seconds).name().to_string()
+    }
+
+    pub fn abandon(&mut self) {
+        self.inner.abandon();
+    }
+
+    // "IN_PROGRESS", "WON", "LOST", "LOST_SUDDEN_DEATH" or "ABANDONED"
+    pub fn status(&self) -> String {
+        self.inner.status().name().to_string()
+    }
+
+    pub fn target(&self) -> String {
+        self.inner.target()
+    }
+
+    // "correct", "present", "absent", or null for a key not yet coloured
+    pub fn key_state(&self, key: char) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.inner.key_state(key)).unwrap()
+    }
+
+    // `{ status, rows, guesses: [{ equation, feedback }], hints_used, locked }`
+    pub fn state(&self) -> JsValue {
+        let view = GameView {
+            status: self.inner.status(),
+            rows: self.inner.rows(),
+            guesses: self.inner.guesses(),
+            hints_used: self.inner.hints_used(),
+            locked: (0..self.inner.length()).map(|i| self.inner.locked(i)).collect(),
+        };
+        serde_wasm_bindgen::to_value(&view).unwrap()
+    }
+}
+
+#[wasm_bindgen(js_name = SumzleSolver)]
+pub struct WasmSumzleSolver {
+    inner: SumzleSolver,
+}
+
+#[wasm_bindgen(js_class = SumzleSolver)]
+impl WasmSumzleSolver {
+    #[wasm_bindgen(constructor)]
+    pub fn new(length: usize, max_operand_value: i32) -> Self {
+        console::log_1(&"SumzleSolver initialized".into());
+
+        Self { inner: SumzleSolver::new(length, max_operand_value) }
+    }
+
+    // Solver that only places the symbols in `symbols`, e.g.
+    // "0123456789+-*/=" for a level without `A`, `!`, `^`, `%` or brackets
+    pub fn with_symbols(length: usize, max_operand_value: i32, symbols: &str) -> Self {
+        Self { inner: SumzleSolver::with_symbols(length, max_operand_value, symbols.chars().collect()) }
+    }
+
+    // Switch between the "classic" solver rules and the "game" compatible ones
+    pub fn set_rule_profile(&mut self, profile: &str) -> bool {
+        self.inner.set_rule_profile(profile)
+    }
+
+    // Load a dictionary written by `sumzle dict` (e.g. the bytes of a fetched
+    // `.szd` file) so `search` filters it instead of exploring the search tree
+    pub fn load_dictionary(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        Dictionary::from_bytes(bytes)
+            .and_then(|dictionary| self.inner.load_dictionary(dictionary))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    pub fn evaluate_expression(&self, expr: &str) -> Option<i32> {
+        self.inner.evaluate_expression(expr)
+    }
+
+    // Evaluate an expression, returning `{ ok: true, value }` or
+    // `{ ok: false, error: { kind, message } }`
+    pub fn evaluate(&self, expr: &str) -> JsValue {
+        let outcome = match self.inner.try_evaluate_expression(expr) {
+            Ok(value) => EvalOutcome { ok: true, value: Some(value), error: None },
+            Err(e) => EvalOutcome { ok: false, value: None, error: Some(e.to_info()) },
+        };
+        serde_wasm_bindgen::to_value(&outcome).unwrap()
+    }
+
+    // Validate an equation, returning `{ ok: true }` or
+    // `{ ok: false, error: { kind, message } }`
+    pub fn check_equation(&self, expression: &str) -> JsValue {
+        let outcome = match self.inner.validate_equation(expression) {
+            Ok(()) => EvalOutcome { ok: true, value: None, error: None },
+            Err(e) => EvalOutcome { ok: false, value: None, error: Some(e.to_info()) },
+        };
+        serde_wasm_bindgen::to_value(&outcome).unwrap()
+    }
+
+    pub fn is_valid_solution(&self, expression: &str) -> bool {
+        self.inner.is_valid_solution(expression)
+    }
+
+    // Best `top_n` next guesses as `[{ guess, score, worst_case, is_candidate }]`,
+    // where `score` is the expected information in bits. `mode` is "entropy"
+    // or "minimax".
+    pub fn recommend_guess(&self, constraints_json: &str, top_n: usize, include_non_candidates: bool, mode: &str) -> JsValue {
+        let mode = RecommendMode::from_name(mode).unwrap_or_default();
+        let outcome = Constraints::from_json(constraints_json)
+            .and_then(|c| self.inner.recommend_guess(&c, top_n, include_non_candidates, mode));
+        let suggestions = match outcome {
+            Ok(suggestions) => suggestions,
+            Err(e) => {
+                console_log!("Error preprocessing constraints: {}", e);
+                Vec::new()
+            },
+        };
+        serde_wasm_bindgen::to_value(&suggestions).unwrap()
+    }
+
+    // Implementation of the search algorithm
+    pub fn search(&self, constraints_json: &str) -> JsValue {
+        console_log!("Search called with constraints: {}", constraints_json);
+
+        let outcome = Constraints::from_json(constraints_json).and_then(|c| self.inner.search_counted(&c));
+        let results = match outcome {
+            Ok((results, searched_count)) => {
+                console_log!("Search completed. Found {} results. Searched {} expressions.", results.len(), searched_count);
+                results
+            },
+            Err(e) => {
+                console_log!("Error preprocessing constraints: {}", e);
+                Vec::new()
+            },
+        };
+
+        // Return results
+        serde_wasm_bindgen::to_value(&results).unwrap()
+    }
+    // Search shard `shard_idx` of `shard_count`, returning
+    // `{ new_results, searched, done }`. Each Web Worker runs its own solver
+    // on one shard; concatenating the shards' results in index order gives
+    // the same list as `search`.
+    pub fn search_shard(&self, constraints_json: &str, shard_idx: usize, shard_count: usize) -> JsValue {
+        let outcome = Constraints::from_json(constraints_json).and_then(|c| self.inner.search_shard(&c, shard_idx, shard_count));
+        let shard = match outcome {
+            Ok(shard) => shard,
+            Err(e) => {
+                console_log!("Error preprocessing constraints: {}", e);
+                StepResult { done: true, ..StepResult::default() }
+            },
+        };
+        serde_wasm_bindgen::to_value(&shard).unwrap()
+    }
+
+    // Start a search that JS advances with `step(budget)`, so long searches
+    // can run in time slices, report progress and be cancelled
+    pub fn start_search(&self, constraints_json: &str) -> Result<WasmSearchSession, JsValue> {
+        Constraints::from_json(constraints_json)
+            .and_then(|c| self.inner.start_search(&c))
+            .map(|inner| WasmSearchSession { inner })
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+#[wasm_bindgen(js_name = SearchSession)]
+pub struct WasmSearchSession {
+    inner: SearchSession,
+}
+
+#[wasm_bindgen(js_class = SearchSession)]
+impl WasmSearchSession {
+    // Try at most `budget` character placements, returning
+    // `{ new_results, searched, done }`
+    pub fn step(&mut self, budget: usize) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.inner.step(budget)).unwrap()
+    }
+
+    pub fn cancel(&mut self) {
+        self.inner.cancel();
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.inner.is_done()
+    }
+
+    pub fn searched(&self) -> usize {
+        self.inner.searched()
+    }
+}
@@ -0,0 +1,33 @@
+// Module Web Worker running one shard of a solver search.
+//
+// Start it with `new Worker('./sumzle_worker.js', { type: 'module' })` and post
+// { length, maxOperandValue, ruleProfile, constraints, shardIdx, shardCount };
+// it replies with { shardIdx, new_results, searched, done }. Concatenating the
+// replies' `new_results` in shard order gives the same list as `search`.
+//
+// A `dist` built before `search_shard` existed cannot run shards; the worker
+// then replies with { shardIdx, error } so the page can fall back to `search`
+// on the main thread. Run `build.sh` to rebuild it.
+import init, { SumzleSolver } from './dist/sumzle_solver.js';
+
+const ready = init();
+
+const supportsShards = typeof SumzleSolver.prototype.search_shard === 'function';
+
+self.onmessage = async (event) => {
+    const { length, maxOperandValue, ruleProfile, constraints, shardIdx, shardCount } = event.data;
+    if (!supportsShards) {
+        self.postMessage({ shardIdx, error: 'dist/sumzle_solver.js has no search_shard; rebuild it with build.sh' });
+        return;
+    }
+    await ready;
+
+    const solver = new SumzleSolver(length, maxOperandValue);
+    if (ruleProfile) {
+        solver.set_rule_profile(ruleProfile);
+    }
+    const shard = solver.search_shard(constraints, shardIdx, shardCount);
+    solver.free();
+
+    self.postMessage({ shardIdx, ...shard });
+};
@@ -0,0 +1,30 @@
+use sumzle_solver::bounds::expression_ranges;
+use sumzle_solver::{RuleSet, SumzleSolver};
+
+// Every expression the search can put on either side of a relation must lie
+// within the bound for its width, signs included.
+#[test]
+fn expression_ranges_cover_every_short_expression() {
+    let alphabet: Vec<char> = "0123456789+-*/%^()![]A".chars().collect();
+    for rules in [RuleSet::classic(), RuleSet::game_compatible()] {
+        let solver = SumzleSolver::with_rules(4, 30, rules);
+        let ranges = expression_ranges(4, 30, &rules);
+
+        for width in 1..=4u32 {
+            for mut code in 0..alphabet.len().pow(width) {
+                let mut expr = String::new();
+                for _ in 0..width {
+                    expr.push(alphabet[code % alphabet.len()]);
+                    code /= alphabet.len();
+                }
+                if expr.split(|c: char| !c.is_ascii_digit()).any(|n| n.parse::<i32>().is_ok_and(|n| n > 30)) {
+                    continue;
+                }
+                if let Ok(value) = solver.try_evaluate_expression(&expr) {
+                    let range = ranges[width as usize].unwrap_or_else(|| panic!("no range for {}", expr));
+                    assert!(range.lo <= value as f64 && value as f64 <= range.hi, "{} = {} outside {:?}", expr, value, range);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const FIRST_ROW: &str = "1+2=3:YGBGB";
+const FIRST_ROW_JSON: &str = r#"{"rows":[[
+    {"char":"1","state":"present"},{"char":"+","state":"correct"},{"char":"2","state":"empty"},
+    {"char":"=","state":"correct"},{"char":"3","state":"empty"}]]}"#;
+
+fn sumzle(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sumzle"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Rejected arguments exit without reading stdin, closing the pipe early
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+// The candidates printed before the count
+fn candidates(output: &Output) -> Vec<String> {
+    let text = stdout(output);
+    let lines: Vec<&str> = text.lines().collect();
+    let count = lines.iter().position(|line| line.starts_with("Candidates: ")).unwrap();
+    assert_eq!(lines[count], format!("Candidates: {}", count));
+    lines[..count].iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn compact_rows_are_solved() {
+    let output = sumzle(&[FIRST_ROW, "4+6=9:BGYGB"], "");
+    assert_eq!(candidates(&output), ["5+1=6", "6+1=7"]);
+    assert!(stdout(&output).contains("Recommended: 5+1=6 (1.00 bits, worst case 1)"));
+}
+
+#[test]
+fn json_rows_are_read_from_a_file_or_stdin() {
+    let expected = candidates(&sumzle(&[FIRST_ROW], ""));
+    assert_eq!(expected.len(), 6);
+
+    let path = std::env::temp_dir().join(format!("sumzle-cli-{}.json", std::process::id()));
+    std::fs::write(&path, FIRST_ROW_JSON).unwrap();
+    let from_file = sumzle(&["--json", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(candidates(&from_file), expected);
+
+    assert_eq!(candidates(&sumzle(&["--json", "-"], FIRST_ROW_JSON)), expected);
+    // A document given in place of the rows
+    assert_eq!(candidates(&sumzle(&[FIRST_ROW_JSON], "")), expected);
+}
+
+#[test]
+fn conflicting_rows_exit_with_status_2() {
+    let output = sumzle(&["1+2=3:GGGGG", "1+2=3:BBBBB"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Conflict"));
+}
+
+#[test]
+fn bad_arguments_exit_with_status_1() {
+    let bad: [&[&str]; 8] = [
+        &["--json", "-", FIRST_ROW],
+        &["--bogus"],
+        &["--max"],
+        &["--rules", "strict", FIRST_ROW],
+        &[],
+        &["-l", "2"],
+        &["12:GG"],
+        &["dict", "--length", "2", "--output", "unused.szd"],
+    ];
+    for args in bad {
+        let output = sumzle(args, FIRST_ROW_JSON);
+        assert_eq!(output.status.code(), Some(1), "{:?}", args);
+        assert!(output.stdout.is_empty(), "{:?}", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Usage: sumzle"), "{:?}", args);
+    }
+
+    let output = sumzle(&["--json", "-", FIRST_ROW], FIRST_ROW_JSON);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Give either compact rows or JSON, not both"));
+}
@@ -0,0 +1,57 @@
+use sumzle_solver::daily::daily_seed;
+use sumzle_solver::expr::parse_equation;
+use sumzle_solver::{daily_puzzle, Date, Difficulty, RuleSet, SumzleSolver};
+
+#[test]
+fn dates_count_days_from_the_epoch() {
+    assert_eq!(Date::new(1970, 1, 1).unwrap().days_since_epoch(), 0);
+    assert_eq!(Date::new(1969, 12, 31).unwrap().days_since_epoch(), -1);
+    assert_eq!(Date::parse("2000-02-29").unwrap().days_since_epoch(), 11016);
+    assert_eq!(Date::parse("2026-10-16").unwrap().days_since_epoch(), 20742);
+
+    for invalid in ["2023-02-29", "2024-13-01", "2024-04-31", "2024-1-01", "2024-01-01-", "abcd-01-01", ""] {
+        assert_eq!(Date::parse(invalid), None, "{}", invalid);
+    }
+}
+
+// Every player derives the same puzzle from the date, in the browser and
+// natively, so these values must only change deliberately
+#[test]
+fn daily_puzzles_are_stable() {
+    let expected = [
+        ("1970-01-01", ["1!=1", "(9-6)+8%4=3", "6A5=720"]),
+        ("2000-02-29", ["[1/5]=0", "4A1+5A2=24", "5!+4A4=144"]),
+        ("2024-12-31", ["5!+[1/5]=120", "7A2=42", "(8*5A5)%18=6"]),
+        ("2026-10-16", ["5*(7+5)=60", "2A1%[6/5]=0", "4A1*[5/5]=4"]),
+    ];
+    for (date, puzzles) in expected {
+        let date = Date::parse(date).unwrap();
+        for (difficulty, puzzle) in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard].into_iter().zip(puzzles) {
+            assert_eq!(daily_puzzle(date, difficulty).text, puzzle, "{:?} {:?}", date, difficulty);
+        }
+    }
+}
+
+#[test]
+fn daily_puzzles_are_valid_and_differ_by_day() {
+    // Valid under the exact rules they are generated with, and in the game
+    let solvers = [RuleSet::classic(), RuleSet::game_compatible()].map(|rules| SumzleSolver::with_rules(0, 0, rules));
+    let start = Date::parse("2026-01-01").unwrap();
+    let mut seeds = Vec::new();
+    for day in 1..=28 {
+        let date = Date::new(2026, 2, day).unwrap();
+        assert!(date > start);
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let puzzle = daily_puzzle(date, difficulty);
+            for solver in &solvers {
+                assert!(solver.is_valid_solution(&puzzle.text), "{}", puzzle.text);
+            }
+            assert_eq!(parse_equation(&puzzle.text).unwrap(), puzzle.equation);
+            assert_eq!(daily_puzzle(date, difficulty), puzzle);
+            seeds.push(daily_seed(date, difficulty));
+        }
+    }
+    seeds.sort_unstable();
+    seeds.dedup();
+    assert_eq!(seeds.len(), 28 * 3);
+}
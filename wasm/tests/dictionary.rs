@@ -0,0 +1,113 @@
+use sumzle_solver::{CharSet, Constraints, Dictionary, DictionaryError, RuleSet, SumzleSolver};
+
+fn sorted(mut results: Vec<String>) -> Vec<String> {
+    results.sort();
+    results
+}
+
+#[test]
+fn dictionary_round_trips_through_bytes() {
+    let dictionary = Dictionary::generate(&SumzleSolver::new(5, 30));
+    assert!(dictionary.equations().contains(&"1+2=3".to_string()));
+    assert!(dictionary.equations().windows(2).all(|w| w[0] < w[1]));
+
+    let bytes = dictionary.to_bytes();
+    assert!(bytes.len() < dictionary.len() * 5, "{} bytes for {} equations", bytes.len(), dictionary.len());
+    assert_eq!(Dictionary::from_bytes(&bytes), Ok(dictionary));
+}
+
+#[test]
+fn damaged_dictionaries_are_rejected() {
+    let bytes = Dictionary::generate(&SumzleSolver::new(5, 30)).to_bytes();
+    assert_eq!(Dictionary::from_bytes(b"nope"), Err(DictionaryError::BadMagic));
+    assert_eq!(Dictionary::from_bytes(&bytes[..bytes.len() - 1]), Err(DictionaryError::Truncated));
+
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert_eq!(Dictionary::from_bytes(&longer), Err(DictionaryError::TrailingBytes));
+}
+
+#[test]
+fn search_filters_a_loaded_dictionary() {
+    let rows = ["1+2=3:BBBGG", "8-5=3:BGBGG"];
+    for rules in [RuleSet::classic(), RuleSet::game_compatible()] {
+        let solver = SumzleSolver::with_rules(5, 30, rules);
+        let dictionary = Dictionary::generate(&solver);
+        for n in 0..=rows.len() {
+            let constraints = Constraints::from_compact(&rows[..n]).unwrap();
+            let mut filtering = SumzleSolver::with_rules(5, 30, rules);
+            filtering.load_dictionary(dictionary.clone()).unwrap();
+            let (results, checked) = filtering.search_counted(&constraints).unwrap();
+            assert_eq!(checked, dictionary.len());
+            assert_eq!(results, sorted(solver.search(&constraints).unwrap()));
+        }
+    }
+}
+
+#[test]
+fn dictionary_search_matches_the_tree_with_fixed_signs() {
+    // Fixed tiles put signs and zeros where the search would not try them
+    // on its own, so the dictionary must hold those equations too
+    let boards: [(usize, &[&str]); 5] = [
+        (4, &["5>-3:GGGG"]),
+        (4, &["0>-1:BGGB"]),
+        (5, &["3>-12:BGGBB"]),
+        (5, &["9>+12:BGGBB"]),
+        (5, &["0*1=0:GBBBB"]),
+    ];
+    for rules in [RuleSet::classic(), RuleSet::game_compatible()] {
+        for (length, rows) in boards {
+            let constraints = Constraints::from_compact(rows).unwrap();
+            let solver = SumzleSolver::with_rules(length, 30, rules);
+            let tree = sorted(solver.search(&constraints).unwrap());
+            let mut filtering = SumzleSolver::with_rules(length, 30, rules);
+            filtering.load_dictionary(Dictionary::generate(&solver)).unwrap();
+            assert!(!tree.is_empty(), "{:?}", rows);
+            assert_eq!(filtering.search(&constraints).unwrap(), tree, "{:?}", rows);
+        }
+    }
+}
+
+#[test]
+fn load_dictionary_after_a_cached_search() {
+    let first = Constraints::from_compact(&["1+2=3:BBBGG"]).unwrap();
+    let second = Constraints::from_compact(&["1+2=3:BBBGG", "8-5=3:BGBGG"]).unwrap();
+    let dictionary = Dictionary::generate(&SumzleSolver::new(5, 30));
+    let mut solver = SumzleSolver::new(5, 30);
+    let tree = solver.search(&first).unwrap();
+    solver.load_dictionary(dictionary.clone()).unwrap();
+
+    // The tree's cached results are dropped rather than filtered
+    let (results, checked) = solver.search_counted(&second).unwrap();
+    assert_eq!(checked, dictionary.len());
+    assert_eq!(results, sorted(SumzleSolver::new(5, 30).search(&second).unwrap()));
+    assert_eq!(solver.search(&first).unwrap(), sorted(tree));
+}
+
+#[test]
+fn dictionaries_only_load_into_matching_solvers() {
+    let dictionary = Dictionary::generate(&SumzleSolver::new(5, 30));
+    assert_eq!(SumzleSolver::new(6, 30).load_dictionary(dictionary.clone()), Err(DictionaryError::Mismatch));
+    assert_eq!(SumzleSolver::new(5, 99).load_dictionary(dictionary.clone()), Err(DictionaryError::Mismatch));
+
+    // After a rule change the dictionary is ignored rather than misapplied
+    let mut solver = SumzleSolver::new(5, 30);
+    solver.load_dictionary(dictionary).unwrap();
+    solver.set_rule_profile("game");
+    let mut game = SumzleSolver::new(5, 30);
+    game.set_rule_profile("game");
+    assert_eq!(solver.search(&Constraints::default()), game.search(&Constraints::default()));
+}
+
+#[test]
+fn dictionaries_serve_solvers_with_fewer_symbols() {
+    let symbols: CharSet = "0123456789+-*=".chars().collect();
+    let full = Dictionary::generate(&SumzleSolver::new(5, 30));
+    let reduced = Dictionary::generate(&SumzleSolver::with_symbols(5, 30, symbols));
+    assert_eq!(Dictionary::from_bytes(&reduced.to_bytes()), Ok(reduced.clone()));
+    assert_eq!(SumzleSolver::new(5, 30).load_dictionary(reduced.clone()), Err(DictionaryError::Mismatch));
+
+    let mut solver = SumzleSolver::with_symbols(5, 30, symbols);
+    solver.load_dictionary(full).unwrap();
+    assert_eq!(solver.search(&Constraints::default()).unwrap(), reduced.equations());
+}
@@ -0,0 +1,95 @@
+use sumzle_solver::expr::{ParseError, Token};
+use sumzle_solver::rational::ArithmeticError;
+use sumzle_solver::{EvalError, RuleSet, SumzleSolver};
+
+// The `kind` strings are part of the JS API
+#[test]
+fn kinds_are_stable() {
+    let kinds = [
+        (EvalError::Empty, "empty"),
+        (EvalError::InvalidChar('x'), "invalid_char"),
+        (EvalError::LeadingZero, "leading_zero"),
+        (EvalError::NumberTooLarge, "number_too_large"),
+        (EvalError::Syntax, "syntax"),
+        (EvalError::UnmatchedBracket, "unmatched_bracket"),
+        (EvalError::MissingRelation, "missing_relation"),
+        (EvalError::MultipleRelations, "multiple_relations"),
+        (EvalError::EmptySide, "empty_side"),
+        (EvalError::DivisionByZero, "division_by_zero"),
+        (EvalError::Overflow, "overflow"),
+        (EvalError::Irrational, "irrational"),
+        (EvalError::FactorialOutOfRange, "factorial_out_of_range"),
+        (EvalError::InvalidPermutation, "invalid_permutation"),
+        (EvalError::NonInteger, "non_integer"),
+        (EvalError::InvalidResult, "invalid_result"),
+        (EvalError::NotSatisfied, "not_satisfied"),
+    ];
+    for (error, kind) in kinds {
+        assert_eq!(error.kind(), kind);
+        let info = error.to_info();
+        assert_eq!((info.kind, info.message.as_str()), (kind, error.to_string().as_str()));
+        assert_eq!(
+            serde_json::to_value(&info).unwrap(),
+            serde_json::json!({ "kind": kind, "message": error.message() })
+        );
+    }
+    assert_eq!(EvalError::InvalidChar('x').message(), "无效字符: x");
+}
+
+#[test]
+fn parse_errors_map_to_eval_errors() {
+    let mappings = [
+        (ParseError::Empty, EvalError::Empty),
+        (ParseError::InvalidChar('?'), EvalError::InvalidChar('?')),
+        (ParseError::LeadingZero, EvalError::LeadingZero),
+        (ParseError::NumberTooLarge, EvalError::NumberTooLarge),
+        (ParseError::UnexpectedToken(Token::Star), EvalError::Syntax),
+        (ParseError::UnexpectedEnd, EvalError::Syntax),
+        (ParseError::UnmatchedBracket, EvalError::UnmatchedBracket),
+        (ParseError::MissingRelation, EvalError::MissingRelation),
+        (ParseError::MultipleRelations, EvalError::MultipleRelations),
+        (ParseError::EmptySide, EvalError::EmptySide),
+    ];
+    for (parse_error, eval_error) in mappings {
+        assert_eq!(EvalError::from(parse_error), eval_error, "{:?}", parse_error);
+    }
+}
+
+#[test]
+fn arithmetic_errors_map_to_eval_errors() {
+    assert_eq!(EvalError::from(ArithmeticError::Overflow), EvalError::Overflow);
+    assert_eq!(EvalError::from(ArithmeticError::DivisionByZero), EvalError::DivisionByZero);
+    assert_eq!(EvalError::from(ArithmeticError::Irrational), EvalError::Irrational);
+}
+
+#[test]
+fn solvers_report_the_reason_for_a_rejection() {
+    let classic = SumzleSolver::new(0, 0);
+    let cases = [
+        ("1+1", EvalError::MissingRelation),
+        ("1=1=1", EvalError::MultipleRelations),
+        ("=1", EvalError::EmptySide),
+        ("1+=2", EvalError::Syntax),
+        ("1**2=1", EvalError::Syntax),
+        ("(2]=2", EvalError::UnmatchedBracket),
+        ("01=1", EvalError::LeadingZero),
+        ("1x=1", EvalError::InvalidChar('x')),
+        ("1/0=1", EvalError::DivisionByZero),
+        ("2^(1/2)=1", EvalError::Irrational),
+        ("9^9^9=1", EvalError::Overflow),
+        ("13!=1", EvalError::FactorialOutOfRange),
+        ("2A3=1", EvalError::InvalidPermutation),
+        ("7/2=3", EvalError::NonInteger),
+        ("1=2", EvalError::NotSatisfied),
+    ];
+    for (equation, error) in cases {
+        assert_eq!(classic.validate_equation(equation), Err(error), "{}", equation);
+    }
+    assert_eq!(classic.try_evaluate_expression(""), Err(EvalError::Empty));
+
+    let game = SumzleSolver::with_rules(0, 0, RuleSet::game_compatible());
+    assert_eq!(game.validate_equation("0/0=1"), Err(EvalError::InvalidResult));
+    // The game checks the relation before integrality
+    assert_eq!(game.validate_equation("7/2=3"), Err(EvalError::NotSatisfied));
+    assert_eq!(game.validate_equation("7/2>3"), Err(EvalError::NonInteger));
+}
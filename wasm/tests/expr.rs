@@ -0,0 +1,127 @@
+use sumzle_solver::expr::{
+    parse_equation, parse_expression, parse_expression_with, tokenize, BinOp, Equation, Expr, Grammar, ParseError, Relation, Token,
+};
+
+fn num(n: i64) -> Box<Expr> {
+    Box::new(Expr::Num(n))
+}
+
+fn bin(op: BinOp, lhs: Box<Expr>, rhs: Box<Expr>) -> Box<Expr> {
+    Box::new(Expr::BinOp(op, lhs, rhs))
+}
+
+fn parse(input: &str) -> Expr {
+    parse_expression(input).unwrap_or_else(|e| panic!("{}: {:?}", input, e))
+}
+
+#[test]
+fn tokens_cover_every_symbol() {
+    assert_eq!(
+        tokenize("12+3-4*5/6%7^8A9!()[]=>0").unwrap(),
+        [
+            Token::Num(12),
+            Token::Plus,
+            Token::Num(3),
+            Token::Minus,
+            Token::Num(4),
+            Token::Star,
+            Token::Num(5),
+            Token::Slash,
+            Token::Num(6),
+            Token::Percent,
+            Token::Num(7),
+            Token::Caret,
+            Token::Num(8),
+            Token::Perm,
+            Token::Num(9),
+            Token::Bang,
+            Token::LParen,
+            Token::RParen,
+            Token::LFloor,
+            Token::RFloor,
+            Token::Eq,
+            Token::Gt,
+            Token::Num(0),
+        ]
+    );
+}
+
+#[test]
+fn precedence_and_associativity() {
+    // Unary minus binds looser than `^`
+    assert_eq!(parse("-2^2"), Expr::Neg(bin(BinOp::Pow, num(2), num(2))));
+    // `^` is right associative, `-` and `/` are left associative
+    assert_eq!(parse("2^3^2"), *bin(BinOp::Pow, num(2), bin(BinOp::Pow, num(3), num(2))));
+    assert_eq!(parse("8-3-2"), *bin(BinOp::Sub, bin(BinOp::Sub, num(8), num(3)), num(2)));
+    assert_eq!(parse("8/4/2"), *bin(BinOp::Div, bin(BinOp::Div, num(8), num(4)), num(2)));
+    assert_eq!(parse("1+2*3^2"), *bin(BinOp::Add, num(1), bin(BinOp::Mul, num(2), bin(BinOp::Pow, num(3), num(2)))));
+    assert_eq!(parse("7%2*3"), *bin(BinOp::Mul, bin(BinOp::Mod, num(7), num(2)), num(3)));
+    assert_eq!(parse("+5"), Expr::Num(5));
+}
+
+#[test]
+fn factorial_and_permutation_follow_the_grammar() {
+    let classic = Grammar::default();
+    let game = Grammar { permutation_before_factorial: true };
+    assert_eq!(parse_expression_with("3A2!", classic).unwrap(), Expr::Perm(num(3), Box::new(Expr::Factorial(num(2)))));
+    assert_eq!(parse_expression_with("3A2!", game).unwrap(), Expr::Factorial(Box::new(Expr::Perm(num(3), num(2)))));
+
+    for grammar in [classic, game] {
+        assert_eq!(parse_expression_with("3!A2", grammar).unwrap(), Expr::Perm(Box::new(Expr::Factorial(num(3))), num(2)));
+        assert_eq!(parse_expression_with("2^3A2", grammar).unwrap(), *bin(BinOp::Pow, num(2), Box::new(Expr::Perm(num(3), num(2)))));
+        assert_eq!(parse_expression_with("3!!", grammar).unwrap(), Expr::Factorial(Box::new(Expr::Factorial(num(3)))));
+    }
+}
+
+#[test]
+fn brackets_group_and_floor() {
+    assert_eq!(parse("(2+1)!"), Expr::Factorial(Box::new(Expr::Paren(bin(BinOp::Add, num(2), num(1))))));
+    assert_eq!(parse("(3)A2"), Expr::Perm(Box::new(Expr::Paren(num(3))), num(2)));
+    assert_eq!(
+        parse("[(7+1)/3]"),
+        Expr::Floor(bin(BinOp::Div, Box::new(Expr::Paren(bin(BinOp::Add, num(7), num(1)))), num(3)))
+    );
+    assert_eq!(parse("-(2)^2"), Expr::Neg(bin(BinOp::Pow, Box::new(Expr::Paren(num(2))), num(2))));
+}
+
+#[test]
+fn equations_split_at_the_top_level_relation() {
+    assert_eq!(
+        parse_equation("2*3>5").unwrap(),
+        Equation { lhs: *bin(BinOp::Mul, num(2), num(3)), relation: Relation::Gt, rhs: Expr::Num(5) }
+    );
+    assert_eq!(parse_equation("1=-1+2").unwrap().rhs, *bin(BinOp::Add, Box::new(Expr::Neg(num(1))), num(2)));
+    // A relation inside brackets is not the equation's
+    assert_eq!(parse_equation("(1=1)=1"), Err(ParseError::UnexpectedToken(Token::Eq)));
+}
+
+#[test]
+fn every_parse_error_is_reported() {
+    assert_eq!(parse_expression(""), Err(ParseError::Empty));
+    assert_eq!(parse_expression("2x3"), Err(ParseError::InvalidChar('x')));
+
+    assert_eq!(parse_expression("05"), Err(ParseError::LeadingZero));
+    assert_eq!(parse_equation("1+00=1"), Err(ParseError::LeadingZero));
+    assert_eq!(parse("0"), Expr::Num(0));
+    assert_eq!(parse("100"), Expr::Num(100));
+    assert_eq!(parse_expression("9223372036854775807"), Ok(Expr::Num(i64::MAX)));
+    assert_eq!(parse_expression("9223372036854775808"), Err(ParseError::NumberTooLarge));
+
+    assert_eq!(parse_expression("2+*3"), Err(ParseError::UnexpectedToken(Token::Star)));
+    assert_eq!(parse_expression("2 3"), Err(ParseError::InvalidChar(' ')));
+    assert_eq!(parse_expression("(2)3"), Err(ParseError::UnexpectedToken(Token::Num(3))));
+    assert_eq!(parse_expression("2+"), Err(ParseError::UnexpectedEnd));
+    assert_eq!(parse_expression("-"), Err(ParseError::UnexpectedEnd));
+
+    for unmatched in [")(", "[)", "(2]", "(2+1", "2)", "[2"] {
+        assert_eq!(parse_expression(unmatched), Err(ParseError::UnmatchedBracket), "{}", unmatched);
+    }
+
+    assert_eq!(parse_equation("1+1"), Err(ParseError::MissingRelation));
+    assert_eq!(parse_equation(""), Err(ParseError::MissingRelation));
+    assert_eq!(parse_equation("1=1=1"), Err(ParseError::MultipleRelations));
+    assert_eq!(parse_equation("2>1=1"), Err(ParseError::MultipleRelations));
+    assert_eq!(parse_equation("=3"), Err(ParseError::EmptySide));
+    assert_eq!(parse_equation("3="), Err(ParseError::EmptySide));
+    assert_eq!(parse_equation(">"), Err(ParseError::EmptySide));
+}
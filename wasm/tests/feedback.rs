@@ -0,0 +1,59 @@
+use sumzle_solver::feedback::{constraints_for, row_states, to_row};
+use sumzle_solver::{score, Constraints, TileState};
+
+use TileState::{Absent, Correct, Present};
+
+fn states(text: &str) -> Vec<TileState> {
+    text.chars()
+        .map(|c| match c {
+            'G' => Correct,
+            'Y' => Present,
+            _ => Absent,
+        })
+        .collect()
+}
+
+#[test]
+fn duplicates_take_the_leftmost_unused_target_character() {
+    // Target 1+2=3 has one 1, taken by the green, so no other 1 is yellow
+    assert_eq!(score("11+1=13", "1+2=3"), states("GBYBYBB"));
+    // Three 1s in the target: one green and two yellows; only one + is left
+    // for the two unmatched ones
+    assert_eq!(score("1+1+1=3", "11+2=13"), states("GYYBYYG"));
+    assert_eq!(score("2+11=13", "12+1=13"), states("YYYGGGG"));
+}
+
+#[test]
+fn greens_consume_characters_before_yellows() {
+    // The 3 at the end is green, so the earlier 3 finds no unused 3 left
+    assert_eq!(score("3+0=3", "1+2=3"), states("BGBGG"));
+    // And the other way round: the first 3 is green, the last is absent
+    assert_eq!(score("3+0=3", "3+0>2"), states("GGGBB"));
+}
+
+#[test]
+fn positions_beyond_the_target_are_absent() {
+    assert_eq!(score("1+2=3+", "1+2=3"), states("GGGGGB"));
+    // The target's 1 and 2 are unused, but not yellow past its end
+    assert_eq!(score("0+3=3+2", "1+2=3"), states("BGBGGBB"));
+    assert_eq!(score("1+2=3=12", "1+2=3"), states("GGGGGBBB"));
+    assert_eq!(score("1+2", "1+2=3"), states("GGG"));
+}
+
+#[test]
+fn rows_round_trip_through_constraints() {
+    let feedback = score("12+3=15", "10+5=15");
+    let row = to_row("12+3=15", &feedback);
+    assert_eq!(row.iter().map(|tile| tile.char.as_str()).collect::<String>(), "12+3=15");
+    assert_eq!(row[1].state, "empty");
+    assert_eq!(row_states(&row), Some(feedback.clone()));
+
+    let constraints = constraints_for(&["12+3=15", "10+5=15"], "10+5=15");
+    assert_eq!(constraints.rows, [row, to_row("10+5=15", &[Correct; 7])]);
+    assert_eq!(constraints, Constraints::from_compact(&["12+3=15:GBGBGGG", "10+5=15:GGGGGGG"]).unwrap());
+    assert_eq!(Constraints::from_json(&serde_json::to_string(&constraints).unwrap()), Ok(constraints));
+
+    let mut unknown = to_row("1+1=2", &[Correct; 5]);
+    unknown[2].state = "green".to_string();
+    assert_eq!(row_states(&unknown), None);
+}
@@ -0,0 +1,175 @@
+use sumzle_solver::{Difficulty, EvalError, GameRules, GameSession, GameStatus, GuessError, Hint, TileState};
+
+use TileState::{Absent, Correct, Present};
+
+#[test]
+fn rows_follow_difficulty_and_one_try_mode() {
+    let rows = |difficulty, one_try| GameRules { difficulty, one_try, sudden_death_seconds: None }.rows();
+    assert_eq!(rows(Difficulty::Easy, false), 8);
+    assert_eq!(rows(Difficulty::Medium, false), 6);
+    assert_eq!(rows(Difficulty::Hard, false), 4);
+    assert_eq!(rows(Difficulty::Easy, true), 1);
+
+    let mut game = GameSession::new("1+2=3", GameRules { one_try: true, ..GameRules::new(Difficulty::Easy) });
+    game.submit("2+1=3").unwrap();
+    assert_eq!(game.status(), GameStatus::Lost);
+    assert_eq!(game.submit("1+2=3"), Err(GuessError::GameOver));
+}
+
+#[test]
+fn guesses_are_validated_and_scored() {
+    let mut game = GameSession::new("1+2=3", GameRules::new(Difficulty::Hard));
+
+    assert_eq!(game.submit("1+2=33"), Err(GuessError::WrongLength { expected: 5, found: 6 }));
+    assert_eq!(game.submit("1+2=4"), Err(GuessError::Invalid(EvalError::NotSatisfied)));
+    assert_eq!(game.submit("01=01"), Err(GuessError::Invalid(EvalError::LeadingZero)));
+    assert!(game.guesses().is_empty());
+
+    assert_eq!(game.submit("2+1=3"), Ok(vec![Present, Correct, Present, Correct, Correct]));
+    assert_eq!(game.remaining_rows(), 3);
+    assert_eq!(game.key_state('2'), Some(Present));
+    assert_eq!(game.key_state('='), Some(Correct));
+    assert_eq!(game.key_state('9'), None);
+    game.submit("4-1=3").unwrap();
+    assert_eq!(game.key_state('4'), Some(Absent));
+    assert_eq!(game.key_state('1'), Some(Present));
+
+    assert_eq!(game.submit("1+2=3"), Ok(vec![Correct; 5]));
+    assert_eq!(game.status(), GameStatus::Won);
+    assert_eq!(game.guesses().len(), 3);
+    assert_eq!(game.guesses()[2].equation, "1+2=3");
+    assert!(game.hint("").is_none());
+}
+
+#[test]
+fn running_out_of_rows_loses() {
+    let mut game = GameSession::new("1+2=3", GameRules::new(Difficulty::Hard));
+    for guess in ["2+1=3", "3-1=2", "3-2=1"] {
+        game.submit(guess).unwrap();
+        assert_eq!(game.status(), GameStatus::InProgress);
+    }
+    game.submit("1+1=2").unwrap();
+    assert_eq!(game.status(), GameStatus::Lost);
+    assert_eq!(game.remaining_rows(), 0);
+}
+
+#[test]
+fn sudden_death_ends_the_game_on_time() {
+    let sudden_death = GameRules { sudden_death_seconds: Some(30), ..GameRules::default() };
+    let mut game = GameSession::new("1+2=3", sudden_death);
+    assert_eq!(game.tick(29), GameStatus::InProgress);
+    assert_eq!(game.tick(30), GameStatus::LostSuddenDeath);
+    assert_eq!(game.submit("1+2=3"), Err(GuessError::GameOver));
+
+    // Without the mode, time does not matter; a finished game stays finished
+    let mut game = GameSession::new("1+2=3", GameRules::default());
+    assert_eq!(game.tick(3600), GameStatus::InProgress);
+    game.abandon();
+    assert_eq!(game.tick(3600), GameStatus::Abandoned);
+}
+
+#[test]
+fn hints_lock_positions_of_the_current_row() {
+    let target = "12+34=46";
+    let mut game = GameSession::new(target, GameRules::default());
+    game.set_seed(7);
+
+    let mut positions = Vec::new();
+    let mut symbols = 0;
+    while let Some(hint) = game.hint("") {
+        match hint {
+            Hint::Position { index, char } => {
+                assert_eq!(target.chars().nth(index), Some(char));
+                assert_eq!(game.locked(index), Some(char));
+                positions.push(index);
+            },
+            Hint::Symbol { char } => {
+                assert!(target.contains(char));
+                assert_eq!(game.key_state(char), Some(Present));
+                symbols += 1;
+            },
+        }
+    }
+    // Hints stop short of filling in the whole row
+    assert_eq!(positions.len(), 7);
+    assert_eq!(game.hints_used(), positions.len() + symbols);
+
+    let index = positions[0];
+    let mut guess: Vec<char> = target.chars().collect();
+    guess[index] = if guess[index] == '9' { '8' } else { '9' };
+    assert_eq!(game.submit(&guess.into_iter().collect::<String>()), Err(GuessError::HintLocked(index)));
+    game.submit(target).unwrap();
+    assert_eq!(game.status(), GameStatus::Won);
+}
+
+#[test]
+fn hint_locks_last_for_one_row() {
+    let target = "12+34=46";
+    let mut game = GameSession::new(target, GameRules::default());
+    game.set_seed(3);
+    let index = loop {
+        if let Some(Hint::Position { index, .. }) = game.hint("") {
+            break index;
+        }
+    };
+
+    // A different valid equation that keeps the locked character
+    let guess = ["12+35=47", "13+33=46", "22+24=46"]
+        .into_iter()
+        .find(|guess| guess.chars().nth(index) == target.chars().nth(index))
+        .unwrap();
+    game.submit(guess).unwrap();
+    assert!((0..8).all(|i| game.locked(i).is_none()));
+    assert_eq!(game.status(), GameStatus::InProgress);
+}
+
+// What the game's `actuallyProvideHint` gives on 12+34=46 with `Math.random`
+// replaced by the same SplitMix64 sequence
+#[test]
+fn hints_follow_the_game_sequence() {
+    use Hint::{Position, Symbol};
+
+    enum Step {
+        // Ask for a hint with the row typed so far
+        Ask(&'static str, Option<Hint>),
+        Submit(&'static str),
+    }
+    use Step::{Ask, Submit};
+
+    let sequences: [(u64, &[Step]); 2] = [
+        (7, &[
+            Ask("", Some(Position { index: 0, char: '1' })),
+            Ask("", Some(Symbol { char: '6' })),
+            Ask("", Some(Position { index: 2, char: '+' })),
+            Ask("1", Some(Position { index: 3, char: '3' })),
+            Submit("13+33=46"),
+            // Only 4 and 7 are wrong, and locking one leaves the other
+            Ask("12+30=42", Some(Position { index: 4, char: '4' })),
+            // Locking 7 would complete the row, and 2 is the only symbol
+            // the keyboard has not coloured
+            Ask("12+30=42", Some(Symbol { char: '2' })),
+            Ask("12+34=4", None),
+        ]),
+        (5, &[
+            Ask("", Some(Position { index: 6, char: '4' })),
+            Ask("", Some(Position { index: 0, char: '1' })),
+            Ask("", Some(Position { index: 3, char: '3' })),
+            // 2, 6, + and = are left, listed digits first as by `Object.keys`
+            Ask("", Some(Symbol { char: '+' })),
+            Ask("", Some(Position { index: 5, char: '=' })),
+            Ask("", Some(Position { index: 1, char: '2' })),
+        ]),
+    ];
+    for (seed, steps) in sequences {
+        let mut game = GameSession::new("12+34=46", GameRules::default());
+        game.set_seed(seed);
+        for step in steps {
+            match step {
+                Ask(input, expected) => assert_eq!(game.hint(input), *expected, "seed {} input {:?}", seed, input),
+                Submit(guess) => {
+                    game.submit(guess).unwrap();
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use sumzle_solver::rng::SplitMix64;
+use sumzle_solver::{CharSet, Difficulty, PuzzleGenerator, RuleSet, SumzleSolver};
+
+fn game_solver() -> SumzleSolver {
+    SumzleSolver::with_rules(0, 0, RuleSet::game_compatible())
+}
+
+#[test]
+fn rng_matches_the_splitmix64_reference() {
+    let mut rng = SplitMix64::new(0);
+    assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+    for _ in 0..1000 {
+        let n = rng.random_int(1, 6);
+        assert!((1..=6).contains(&n));
+        assert!((0.0..1.0).contains(&rng.next_f64()));
+    }
+}
+
+#[test]
+fn generated_equations_are_valid() {
+    let solver = game_solver();
+    let mut generator = PuzzleGenerator::new(42, Difficulty::Medium);
+    for _ in 0..300 {
+        let equation = generator.try_generate().unwrap();
+        assert!((3..=15).contains(&equation.len()), "{}", equation);
+        assert!(solver.is_valid_solution(&equation), "{}", equation);
+    }
+}
+
+#[test]
+fn same_seed_gives_the_same_equations() {
+    let mut a = PuzzleGenerator::new(7, Difficulty::Easy);
+    let mut b = PuzzleGenerator::new(7, Difficulty::Easy);
+    let mut c = PuzzleGenerator::new(8, Difficulty::Easy);
+    let first: Vec<Option<String>> = (0..20).map(|_| a.generate()).collect();
+    assert_eq!(first, (0..20).map(|_| b.generate()).collect::<Vec<_>>());
+    assert_ne!(first, (0..20).map(|_| c.generate()).collect::<Vec<_>>());
+}
+
+#[test]
+fn length_symbols_and_difficulty_are_respected() {
+    let solver = game_solver();
+    for length in [3, 5, 8, 12, 15] {
+        let mut generator = PuzzleGenerator::new(length as u64, Difficulty::Medium);
+        generator.set_length(Some(length));
+        for _ in 0..20 {
+            let equation = generator.try_generate().unwrap();
+            assert_eq!(equation.len(), length, "{}", equation);
+            assert!(solver.is_valid_solution(&equation), "{}", equation);
+        }
+    }
+
+    let symbols: CharSet = "0123456789+-*=".chars().collect();
+    let mut generator = PuzzleGenerator::new(1, Difficulty::Easy);
+    generator.set_symbols(symbols);
+    for _ in 0..100 {
+        let equation = generator.try_generate().unwrap();
+        assert!(equation.chars().all(|c| symbols.contains(c)), "{}", equation);
+    }
+
+    // Hard puzzles are always equalities
+    let mut generator = PuzzleGenerator::new(3, Difficulty::Hard);
+    assert!((0..200).all(|_| !generator.generate().unwrap().contains('>')));
+}
+
+#[test]
+fn fallbacks_keep_to_the_length_and_symbols() {
+    let solver = game_solver();
+    let fallback = |length: Option<usize>, symbols: &str| {
+        let mut generator = PuzzleGenerator::new(5, Difficulty::Medium);
+        generator.set_length(length);
+        generator.set_symbols(symbols.chars().collect());
+        generator.set_max_attempts(0);
+        assert_eq!(generator.try_generate(), None);
+        generator.generate()
+    };
+
+    // `a+a=2a` where it fits, as in the game
+    let equation = fallback(None, "0123456789+=").unwrap();
+    assert!(solver.is_valid_solution(&equation), "{}", equation);
+    assert!(equation.contains('+'), "{}", equation);
+
+    // Otherwise an equation of the requested length using only the symbols
+    for (length, symbols) in [(Some(9), "0123456789-*="), (None, "0123456789+>"), (Some(12), "0123456789*%>()")] {
+        let equation = fallback(length, symbols).unwrap();
+        assert!(solver.is_valid_solution(&equation), "{}", equation);
+        assert!(equation.chars().all(|c| symbols.contains(c)), "{}", equation);
+        assert!(length.map_or((3..=15).contains(&equation.len()), |length| equation.len() == length), "{}", equation);
+    }
+
+    // No equation without a relation
+    assert_eq!(fallback(None, "0123456789+"), None);
+    assert_eq!(fallback(Some(4), "0123456789+"), None);
+}
@@ -0,0 +1,94 @@
+use sumzle_solver::{Dictionary, DictionaryError, Difficulty, PuzzleGenerator, PuzzleGrader, RuleSet, SumzleSolver};
+
+#[test]
+fn grader_plays_the_entropy_solver() {
+    let solver = SumzleSolver::with_rules(5, 30, RuleSet::game_compatible());
+    let dictionary = Dictionary::generate(&solver);
+
+    // Pools come from the dictionary, not from a search
+    let mut grader = PuzzleGrader::new(30, RuleSet::game_compatible());
+    grader.set_max_length(4);
+    grader.add_dictionary(&dictionary).unwrap();
+    assert_eq!(grader.equation_count(5), Some(dictionary.len()));
+
+    let grade = grader.grade("1+2=3");
+    assert_eq!(grade.equation_count, Some(dictionary.len()));
+    let guesses = grade.guesses.unwrap();
+    assert!((1..=8).contains(&guesses), "{}", guesses);
+    assert_eq!(grade.fits(Difficulty::Hard), guesses <= 4);
+    assert_eq!(grader.grade("1+2=3"), grade);
+
+    // 99 is beyond the operand limit, so the solver never considers it
+    let grade = grader.grade("99=99");
+    assert_eq!(grade.guesses, None);
+    assert!(!grade.fits(Difficulty::Easy));
+
+    let classic = Dictionary::generate(&SumzleSolver::new(3, 30));
+    assert_eq!(grader.add_dictionary(&classic), Err(DictionaryError::Mismatch));
+}
+
+#[test]
+fn graded_generation_fits_the_attempt_budget() {
+    let mut grader = PuzzleGrader::new(30, RuleSet::game_compatible());
+    let generate = |grader: &mut PuzzleGrader| {
+        let mut generator = PuzzleGenerator::new(11, Difficulty::Hard);
+        generator.set_length(Some(5));
+        (0..5).map(|_| generator.try_generate_graded(grader).unwrap()).collect::<Vec<_>>()
+    };
+
+    let puzzles = generate(&mut grader);
+    for (target, grade) in &puzzles {
+        assert_eq!(target.len(), 5);
+        assert!(grade.fits(Difficulty::Hard), "{} {:?}", target, grade);
+        assert_eq!(grader.grade(target), *grade);
+    }
+    assert_eq!(generate(&mut grader), puzzles);
+}
+
+#[test]
+fn difficulty_ratings_follow_simulated_play() {
+    let mut grader = PuzzleGrader::new(30, RuleSet::game_compatible());
+    grader.set_max_length(5);
+
+    let rating = grader.rate_difficulty("4!=24").unwrap();
+    assert!((1..=5).contains(&rating.stars));
+    assert!(rating.expected_guesses >= 1.0);
+    assert!(rating.expected_guesses <= rating.worst_case_guesses as f64);
+    assert_eq!(grader.rate_difficulty("4!=24"), Some(rating));
+
+    // A factorial pins the target down sooner than a lone number repeated
+    let easy = grader.rate_difficulty("4!=24").unwrap();
+    let hard = grader.rate_difficulty("15=15").unwrap();
+    assert!(easy.expected_guesses < hard.expected_guesses, "{:?} {:?}", easy, hard);
+    assert!(easy.stars < hard.stars, "{:?} {:?}", easy, hard);
+
+    assert_eq!(grader.rate_difficulty("99=99"), None);
+}
+
+#[test]
+fn longer_targets_are_played_on_samples() {
+    let mut grader = PuzzleGrader::new(30, RuleSet::game_compatible());
+    assert_eq!(grader.equation_count(8), None);
+
+    for target in ["10+20=30", "3!*4-10=14"] {
+        let grade = grader.grade(target);
+        assert_eq!(grade.equation_count, None);
+        let guesses = grade.guesses.unwrap_or_else(|| panic!("{}", target));
+        assert!((2..=8).contains(&guesses), "{} {}", target, guesses);
+    }
+
+    let grade = grader.grade("10+20=30");
+    let rating = grader.rate_difficulty("10+20=30").unwrap();
+    assert_eq!(Some(rating.optimal_guesses), grade.guesses);
+    assert!((1..=5).contains(&rating.stars));
+    assert_eq!(grader.rate_difficulty("10+20=30"), Some(rating));
+
+    assert_eq!(grader.grade("99+1=100").guesses, None);
+    assert_eq!(grader.rate_difficulty("99+1=100"), None);
+
+    let mut generator = PuzzleGenerator::new(11, Difficulty::Medium);
+    generator.set_length(Some(9));
+    let (target, grade) = generator.try_generate_graded(&mut grader).unwrap();
+    assert_eq!(target.len(), 9);
+    assert!(grade.fits(Difficulty::Medium), "{} {:?}", target, grade);
+}
@@ -0,0 +1,100 @@
+use sumzle_solver::rational::{ArithmeticError, Rational};
+
+fn r(num: i128, den: i128) -> Rational {
+    Rational::new(num, den).unwrap()
+}
+
+fn int(n: i128) -> Rational {
+    Rational::from_integer(n)
+}
+
+#[test]
+fn fractions_are_kept_in_lowest_terms() {
+    assert_eq!(r(2, -4), r(-1, 2));
+    assert_eq!((r(6, 4).numer(), r(6, 4).denom()), (3, 2));
+    assert_eq!(Rational::new(1, 0), Err(ArithmeticError::DivisionByZero));
+    assert_eq!(r(-7, 2).to_string(), "-7/2");
+    assert_eq!(r(8, 4).to_string(), "2");
+
+    // 7/3*3 is exactly 7, not 6.999...
+    let seven = int(7).checked_div(int(3)).unwrap().checked_mul(int(3)).unwrap();
+    assert_eq!(seven, int(7));
+    assert_eq!(seven.to_integer(), Some(7));
+    assert_eq!(r(7, 3).to_integer(), None);
+    assert_eq!(r(1, 3).checked_add(r(1, 6)).unwrap(), r(1, 2));
+    assert_eq!(r(1, 3).checked_sub(r(1, 3)).unwrap(), Rational::ZERO);
+    assert_eq!((r(-7, 2).floor(), r(-7, 2).trunc()), (int(-4), int(-3)));
+}
+
+#[test]
+fn overflow_is_an_error() {
+    let max = int(i128::MAX);
+    assert_eq!(max.checked_add(Rational::ONE), Err(ArithmeticError::Overflow));
+    assert_eq!(int(i128::MIN).checked_neg(), Err(ArithmeticError::Overflow));
+    assert_eq!(int(i128::MIN).checked_sub(Rational::ONE), Err(ArithmeticError::Overflow));
+    assert_eq!(int(1 << 64).checked_mul(int(1 << 64)), Err(ArithmeticError::Overflow));
+    assert_eq!(r(1, 1 << 64).checked_div(int(1 << 64)), Err(ArithmeticError::Overflow));
+    assert_eq!(int(2).checked_pow(int(126)), Ok(int(1 << 126)));
+    assert_eq!(int(2).checked_pow(int(127)), Err(ArithmeticError::Overflow));
+    assert_eq!(int(2).checked_pow(int(1 << 40)), Err(ArithmeticError::Overflow));
+    assert_eq!(int(2).checked_div(Rational::ZERO), Err(ArithmeticError::DivisionByZero));
+}
+
+#[test]
+fn intermediates_of_2_pow_40_stay_exact() {
+    let big = int(1 << 40);
+    assert_eq!(big.checked_mul(big).unwrap(), int(1 << 80));
+    assert_eq!(big.checked_pow(int(3)).unwrap(), int(1 << 120));
+    assert_eq!(big.checked_pow(int(3)).unwrap().checked_mul(big), Err(ArithmeticError::Overflow));
+
+    // Cross-reduction keeps products of large fractions in range
+    let tiny = r(1, 1 << 100);
+    assert_eq!(tiny.checked_mul(int(1 << 100)).unwrap(), Rational::ONE);
+    let third = big.checked_div(int(3)).unwrap();
+    assert_eq!(third.checked_mul(int(3)).unwrap(), big);
+    assert_eq!(third.checked_add(third).unwrap().checked_add(third).unwrap(), big);
+
+    // Unrelated denominators near 2^40 multiply to about 2^80
+    let sum = r(1, 1 << 40).checked_add(r(1, (1 << 40) + 1)).unwrap();
+    assert_eq!(sum, r((1 << 41) + 1, (1 << 80) + (1 << 40)));
+    assert_eq!(sum.checked_sub(r(1, (1 << 40) + 1)).unwrap(), r(1, 1 << 40));
+}
+
+#[test]
+fn fractional_powers_need_an_exact_root() {
+    let pow = |base: Rational, exponent: Rational| base.checked_pow(exponent);
+    assert_eq!(pow(int(4), r(1, 2)), Ok(int(2)));
+    assert_eq!(pow(int(27), r(1, 3)), Ok(int(3)));
+    assert_eq!(pow(int(-8), r(1, 3)), Ok(int(-2)));
+    assert_eq!(pow(r(4, 9), r(1, 2)), Ok(r(2, 3)));
+    assert_eq!(pow(int(8), r(2, 3)), Ok(int(4)));
+    assert_eq!(pow(int(4), r(-1, 2)), Ok(r(1, 2)));
+    assert_eq!(pow(int(1 << 80), r(1, 2)), Ok(int(1 << 40)));
+    assert_eq!(pow(int(1 << 120), r(1, 3)), Ok(int(1 << 40)));
+    assert_eq!(pow(Rational::ZERO, r(1, 2)), Ok(Rational::ZERO));
+    assert_eq!(pow(int(1), r(1, 7)), Ok(int(1)));
+
+    // Irrational roots, even roots of negatives included
+    for (base, exponent) in [(int(2), r(1, 2)), (int(8), r(1, 2)), (r(1, 2), r(1, 2)), (int(-4), r(1, 2)), (int((1 << 120) + 1), r(1, 3))] {
+        assert_eq!(pow(base, exponent), Err(ArithmeticError::Irrational), "{}^({})", base, exponent);
+    }
+
+    assert_eq!(pow(Rational::ZERO, int(0)), Ok(Rational::ONE));
+    assert_eq!(pow(Rational::ZERO, int(-1)), Err(ArithmeticError::DivisionByZero));
+    assert_eq!(pow(int(-1), int(i128::MAX)), Ok(int(-1)));
+    assert_eq!(pow(int(2), int(-3)), Ok(r(1, 8)));
+}
+
+#[test]
+fn remainders_take_the_sign_of_the_dividend() {
+    let rem = |a: Rational, b: Rational| a.checked_rem(b).unwrap();
+    assert_eq!(rem(int(7), int(3)), int(1));
+    assert_eq!(rem(int(-7), int(3)), int(-1));
+    assert_eq!(rem(int(7), int(-3)), int(1));
+    assert_eq!(rem(int(-7), int(-3)), int(-1));
+    assert_eq!(rem(int(6), int(-3)), Rational::ZERO);
+    assert_eq!(rem(r(7, 2), int(2)), r(3, 2));
+    assert_eq!(rem(r(-7, 2), int(2)), r(-3, 2));
+    assert_eq!(rem(int(7), r(5, 2)), int(2));
+    assert_eq!(int(7).checked_rem(Rational::ZERO), Err(ArithmeticError::DivisionByZero));
+}
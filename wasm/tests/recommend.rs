@@ -0,0 +1,101 @@
+use sumzle_solver::recommend::{rank_by_entropy, rank_by_worst_case, rank_guesses, RecommendMode};
+use sumzle_solver::{Constraints, SumzleSolver};
+
+// Two-character boards keep the partitions small enough to work out by
+// hand. Against these candidates:
+//   21  splits them all apart                   1+1+1+1+1+1  log2 6 bits
+//   12  leaves 11 and 13 together               2+1+1+1+1
+//   31  leaves 11 and 21 together, not a candidate
+//   22  cannot tell 11, 13 and 33 apart         3+1+1+1
+//   01  pairs them up, not a candidate          2+2+2        log2 3 bits
+//   00  shares no character with any            6            0 bits
+const CANDIDATES: [&str; 6] = ["11", "12", "13", "21", "22", "33"];
+const POOL: [&str; 6] = ["00", "01", "22", "31", "12", "21"];
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+fn guesses(pool: &[&str], mode: RecommendMode, top_n: usize) -> Vec<String> {
+    rank_guesses(&strings(pool), &strings(&CANDIDATES), top_n, mode).into_iter().map(|s| s.guess).collect()
+}
+
+fn assert_bits(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-12, "{} bits, expected {}", actual, expected);
+}
+
+#[test]
+fn entropy_ranks_by_expected_information() {
+    let ranked = rank_guesses(&strings(&POOL), &strings(&CANDIDATES), POOL.len(), RecommendMode::Entropy);
+    let order: Vec<&str> = ranked.iter().map(|s| s.guess.as_str()).collect();
+    assert_eq!(order, ["21", "12", "31", "22", "01", "00"]);
+
+    let log6 = 6f64.log2();
+    let log3 = 3f64.log2();
+    for (scored, bits) in ranked.iter().zip([log6, 4.0 / 6.0 * log6 + log3 / 3.0, 4.0 / 6.0 * log6 + log3 / 3.0, log6 / 2.0 + 0.5, log3, 0.0]) {
+        assert_bits(scored.score, bits);
+    }
+    assert_eq!(ranked.iter().map(|s| s.worst_case).collect::<Vec<_>>(), [1, 2, 2, 3, 2, 6]);
+    assert_eq!(ranked.iter().map(|s| s.is_candidate).collect::<Vec<_>>(), [true, true, false, true, false, false]);
+
+    assert_eq!(rank_by_entropy(&strings(&POOL), &strings(&CANDIDATES), POOL.len()), ranked);
+}
+
+#[test]
+fn top_n_truncates_the_ranking() {
+    assert_eq!(guesses(&POOL, RecommendMode::Entropy, 2), ["21", "12"]);
+    assert_eq!(guesses(&POOL, RecommendMode::Entropy, 0), Vec::<String>::new());
+    assert_eq!(guesses(&POOL, RecommendMode::Entropy, 100).len(), POOL.len());
+    assert!(rank_guesses(&[], &strings(&CANDIDATES), 3, RecommendMode::Entropy).is_empty());
+}
+
+#[test]
+fn minimax_ranks_by_worst_case_then_entropy() {
+    // 22 gives more information than 01 on average but can leave three
+    // candidates where 01 leaves two
+    assert_eq!(guesses(&POOL, RecommendMode::Minimax, POOL.len()), ["21", "12", "31", "01", "22", "00"]);
+    assert_eq!(
+        rank_by_worst_case(&strings(&POOL), &strings(&CANDIDATES), 4),
+        rank_guesses(&strings(&POOL), &strings(&CANDIDATES), 4, RecommendMode::Minimax)
+    );
+    assert_eq!(RecommendMode::from_name("minimax"), Some(RecommendMode::Minimax));
+    assert_eq!(RecommendMode::from_name("entropy"), Some(RecommendMode::Entropy));
+    assert_eq!(RecommendMode::from_name("greedy"), None);
+}
+
+#[test]
+fn full_ties_prefer_candidates_then_pool_order() {
+    // 23 and 11 both split the candidates 2+2+1+1; only 11 can be the answer
+    for mode in [RecommendMode::Entropy, RecommendMode::Minimax] {
+        assert_eq!(guesses(&["23", "11"], mode, 2), ["11", "23"]);
+        assert_eq!(guesses(&["32", "31"], mode, 2), ["32", "31"]);
+        assert_eq!(guesses(&["31", "32"], mode, 2), ["31", "32"]);
+    }
+}
+
+#[test]
+fn non_candidates_can_split_the_candidates_better() {
+    let solver = SumzleSolver::new(5, 30);
+    let constraints = Constraints::from_compact(&["1+2=3:BGGGB"]).unwrap();
+    let candidates = solver.search(&constraints).unwrap();
+    assert_eq!(candidates.len(), 6);
+
+    // Every candidate is _+2=_, so none tells all six apart
+    let among_candidates = solver.recommend_guess(&constraints, 1, false, RecommendMode::Entropy).unwrap();
+    assert!(among_candidates[0].is_candidate);
+    assert_eq!(among_candidates[0].worst_case, 3);
+
+    for mode in [RecommendMode::Entropy, RecommendMode::Minimax] {
+        let best = &solver.recommend_guess(&constraints, 1, true, mode).unwrap()[0];
+        assert!(!best.is_candidate && !candidates.contains(&best.guess), "{}", best.guess);
+        assert!(solver.is_valid_solution(&best.guess), "{}", best.guess);
+        assert_bits(best.score, 6f64.log2());
+        assert_eq!(best.worst_case, 1);
+        assert!(best.score > among_candidates[0].score);
+    }
+
+    // A single candidate is recommended as is
+    let solved = Constraints::from_compact(&["1+2=3:BGGGB", "2+2=4:GGGGG"]).unwrap();
+    let only = solver.recommend_guess(&solved, 3, true, RecommendMode::Entropy).unwrap();
+    assert_eq!(only.iter().map(|s| s.guess.as_str()).collect::<Vec<_>>(), ["2+2=4"]);
+}
@@ -0,0 +1,59 @@
+use sumzle_solver::{RuleSet, SumzleSolver};
+
+// (equation, accepted by the classic rules, accepted by the game)
+const GOLDEN: &[(&str, bool, bool)] = &[
+    ("1+2=3", true, true),
+    ("12-5*2=2", true, true),
+    ("7/3*3=7", true, true),
+    ("7/2=3", false, false),
+    ("2^3^2=512", true, true),
+    ("2^10>1000", true, true),
+    ("3>5", false, false),
+    ("05+1=6", false, false),
+    ("1+1=2=2", false, false),
+    ("[7/2]=3", true, true),
+    ("[-7/2]=-4", true, false),
+    ("[-7/2]=-3", false, true),
+    ("[(7+1)/3]=2", true, true),
+    ("[[7/2]/2]=1", true, false),
+    ("(2+1)!=6", true, true),
+    ("3!!=720", true, false),
+    ("13!>1", false, true),
+    ("20!>1", false, true),
+    ("21!>1", false, false),
+    ("3A2=6", true, true),
+    ("(3)A2=6", true, true),
+    ("3A2!=6", true, false),
+    ("3A2!=720", false, true),
+    ("3!A2=30", true, false),
+    ("11A0=1", false, true),
+    ("-2^2=-4", true, false),
+    ("(-2)^2=4", true, true),
+    ("5--2=7", true, false),
+    ("5-(-2)=7", true, true),
+    ("7%2=1", true, true),
+    ("7%(5/2)=2", true, false),
+    ("5/0=1", false, false),
+    ("2^31=2147483648", false, true),
+    ("4^(1/2)=2", true, true),
+    // The game's arithmetic is in doubles: no odd roots of negative numbers,
+    // and integers above 2^53 lose their last bits
+    ("(-8)^(1/3)=-2", true, false),
+    ("20!+1>20!", false, false),
+    ("2^53+1>2^53", false, false),
+    ("2^53+2>2^53", false, true),
+    ("1/3*3=1", true, true),
+    ("9/0>1", false, true),
+    ("0/0>-1", false, false),
+];
+
+#[test]
+fn rule_sets_match_golden_table() {
+    let classic = SumzleSolver::with_rules(0, 99, RuleSet::classic());
+    let game = SumzleSolver::with_rules(0, 99, RuleSet::game_compatible());
+
+    for &(equation, classic_ok, game_ok) in GOLDEN {
+        assert_eq!(classic.validate_equation(equation).is_ok(), classic_ok, "classic: {}", equation);
+        assert_eq!(game.validate_equation(equation).is_ok(), game_ok, "game: {}", equation);
+    }
+}
@@ -0,0 +1,239 @@
+use sumzle_solver::recommend::RecommendMode;
+use sumzle_solver::feedback::constraints_for;
+use sumzle_solver::{score, CharSet, ConstraintError, Constraints, RuleSet, SearchSession, SumzleSolver};
+
+#[test]
+fn search_finds_all_solutions_natively() {
+    let solver = SumzleSolver::new(5, 99);
+    let constraints = Constraints::from_compact(&["1+2=3:GGBGB"]).unwrap();
+
+    let results = solver.search(&constraints).unwrap();
+    assert!(!results.is_empty());
+    for r in &results {
+        assert!(r.starts_with("1+") && r.chars().nth(3) == Some('='), "{}", r);
+        assert!(solver.is_valid_solution(r), "{}", r);
+    }
+    assert!(results.contains(&"1+5=6".to_string()));
+}
+
+#[test]
+fn lengths_too_short_for_an_equation_find_nothing() {
+    for length in 0..3 {
+        assert_eq!(SumzleSolver::new(length, 30).search(&Constraints::default()), Ok(Vec::new()), "{}", length);
+    }
+    assert!(SumzleSolver::new(3, 30).search(&Constraints::default()).unwrap().contains(&"1=1".to_string()));
+}
+
+#[test]
+fn search_reports_conflicting_rows() {
+    let solver = SumzleSolver::new(5, 99);
+    let constraints = Constraints::from_compact(&["1+2=3:GBBBB", "4+2=6:GBBBB"]).unwrap();
+
+    assert_eq!(
+        solver.search(&constraints),
+        Err(ConstraintError::FixedConflict { position: 1, first: '1', second: '4' })
+    );
+}
+
+#[test]
+fn search_json_accepts_the_page_format() {
+    let solver = SumzleSolver::new(5, 99);
+    let json = r#"{"rows":[[{"char":"1","state":"correct"},{"char":"+","state":"correct"},{"char":"1","state":"correct"},{"char":"=","state":"correct"},{"char":"2","state":"correct"}]]}"#;
+
+    assert_eq!(solver.search_json(json).unwrap(), vec!["1+1=2".to_string()]);
+}
+
+#[test]
+fn search_session_steps_match_a_full_search() {
+    let solver = SumzleSolver::new(6, 99);
+    let constraints = Constraints::from_compact(&["10-2=8:YBBBGB"]).unwrap();
+    let (expected, expected_searched) = solver.search_counted(&constraints).unwrap();
+
+    let mut session = solver.start_search(&constraints).unwrap();
+    let mut results = Vec::new();
+    let mut steps = 0;
+    loop {
+        let step = session.step(50);
+        results.extend(step.new_results);
+        steps += 1;
+        if step.done {
+            assert_eq!(step.searched, expected_searched);
+            break;
+        }
+    }
+    assert!(steps > 1);
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn search_session_can_be_cancelled() {
+    let solver = SumzleSolver::new(6, 99);
+    let mut session = solver.start_search(&Constraints::default()).unwrap();
+
+    assert!(!session.step(10).done);
+    session.cancel();
+    let step = session.step(10);
+    assert!(step.done && step.new_results.is_empty());
+}
+
+#[test]
+fn prefix_subtrees_partition_the_search() {
+    let solver = SumzleSolver::new(6, 99);
+    let constraints = Constraints::from_compact(&["10-2=8:YBBBGB"]).unwrap();
+    let (expected, expected_searched) = solver.search_counted(&constraints).unwrap();
+
+    let mut results = Vec::new();
+    let mut searched = 0;
+    for prefix in SearchSession::prefixes(&solver, &constraints, 2).unwrap() {
+        let step = SearchSession::with_prefix(&solver, &constraints, &prefix).unwrap().step(usize::MAX);
+        results.extend(step.new_results);
+        searched += step.searched;
+    }
+    assert_eq!(results, expected);
+    assert_eq!(searched, expected_searched);
+}
+
+#[test]
+fn shards_concatenate_to_the_full_search() {
+    let solver = SumzleSolver::new(6, 99);
+    let constraints = Constraints::from_compact(&["10-2=8:YBBBGB"]).unwrap();
+    let (expected, expected_searched) = solver.search_counted(&constraints).unwrap();
+
+    for shard_count in [1, 3, 7] {
+        let mut results = Vec::new();
+        let mut searched = 0;
+        for shard_idx in 0..shard_count {
+            let shard = solver.search_shard(&constraints, shard_idx, shard_count).unwrap();
+            results.extend(shard.new_results);
+            searched += shard.searched;
+        }
+        assert_eq!(results, expected, "{} shards", shard_count);
+        assert_eq!(searched, expected_searched);
+    }
+}
+
+#[test]
+fn right_hand_side_is_computed_from_the_left() {
+    let solver = SumzleSolver::new(6, 99);
+
+    for equation in ["1-3=-2", "1-1=-0", "12/4=3"] {
+        let constraints = Constraints::from_compact(&[format!("{}:GGGGGG", equation)]).unwrap();
+        assert_eq!(solver.search(&constraints).unwrap(), vec![equation.to_string()]);
+    }
+
+    // The only right-hand side for 7*3 uses characters the row rules out
+    let constraints = Constraints::from_compact(&["7*3=21:GGGGBB"]).unwrap();
+    assert!(solver.search(&constraints).unwrap().is_empty());
+}
+
+#[test]
+fn invalid_operands_prune_their_subtrees() {
+    let solver = SumzleSolver::new(7, 99);
+
+    let constraints = Constraints::from_compact(&["13!:GGG"]).unwrap();
+    assert_eq!(solver.search_counted(&constraints).unwrap(), (Vec::new(), 0));
+
+    // Whatever follows 2A3 completes it, even an operator binding as tightly
+    let constraints = Constraints::from_compact(&["2A3:GGG"]).unwrap();
+    assert_eq!(solver.search_counted(&constraints).unwrap(), (Vec::new(), 0));
+
+    // 5/0 is only an operand when nothing binds tighter to the 0
+    let solver = SumzleSolver::new(6, 99);
+    let constraints = Constraints::from_compact(&["5/0:GGG"]).unwrap();
+    let results = solver.search(&constraints).unwrap();
+    assert!(results.contains(&"5/0!=5".to_string()));
+    assert!(results.iter().all(|r| r.starts_with("5/0!")), "{:?}", results);
+}
+
+#[test]
+fn missing_required_characters_prune_early() {
+    // Six characters are known to appear, so almost every prefix that
+    // spends a free position on anything else is cut before the end
+    let solver = SumzleSolver::new(8, 99);
+    let constraints = Constraints::from_compact(&["9*8-7=65:YYYBYGBY"]).unwrap();
+
+    let (results, searched) = solver.search_counted(&constraints).unwrap();
+    assert!(!results.is_empty());
+    assert!(searched < 10 * results.len(), "{} searched for {} results", searched, results.len());
+}
+
+#[test]
+fn added_rows_filter_the_previous_results() {
+    let first = Constraints::from_compact(&["12+3=15:GYBBBGG"]).unwrap();
+    let second = Constraints::from_compact(&["12+3=15:GYBBBGG", "1*2-0=5:GBYBYBG"]).unwrap();
+
+    let solver = SumzleSolver::new(7, 99);
+    let (first_results, _) = solver.search_counted(&first).unwrap();
+    let (second_results, checked) = solver.search_counted(&second).unwrap();
+    assert!(!second_results.is_empty() && second_results.len() < first_results.len());
+    assert_eq!(checked, first_results.len());
+    assert_eq!(second_results, SumzleSolver::new(7, 99).search(&second).unwrap());
+
+    // Dropping a row or changing the rules searches again
+    assert_eq!(solver.search(&first).unwrap(), first_results);
+    let mut solver = solver;
+    solver.set_rule_profile("game");
+    let mut fresh = SumzleSolver::new(7, 99);
+    fresh.set_rule_profile("game");
+    assert_eq!(solver.search(&second).unwrap(), fresh.search(&second).unwrap());
+}
+
+#[test]
+fn recommending_from_every_equation_keeps_the_cached_candidates() {
+    let first = Constraints::from_compact(&["1+2=3:BBBGG"]).unwrap();
+    let second = Constraints::from_compact(&["1+2=3:BBBGG", "8-5=3:BGBGG"]).unwrap();
+    let solver = SumzleSolver::new(5, 30);
+    let candidates = solver.search(&first).unwrap();
+    solver.recommend_guess(&first, 1, true, RecommendMode::Entropy).unwrap();
+
+    let (results, checked) = solver.search_counted(&second).unwrap();
+    assert_eq!(checked, candidates.len());
+    assert_eq!(results, SumzleSolver::new(5, 30).search(&second).unwrap());
+}
+
+#[test]
+fn reduced_symbol_sets_restrict_the_search() {
+    let symbols: CharSet = "0123456789+-*/=".chars().collect();
+    let constraints = Constraints::from_compact(&["9*8-7=65:YBBBBGBB"]).unwrap();
+
+    let full = SumzleSolver::new(8, 99).search(&constraints).unwrap();
+    let reduced = SumzleSolver::with_symbols(8, 99, symbols).search(&constraints).unwrap();
+    let expected: Vec<String> = full.into_iter().filter(|r| r.chars().all(|c| symbols.contains(c))).collect();
+    assert!(!expected.is_empty());
+    assert_eq!(reduced, expected);
+
+    // A green tile on a symbol the level forbids leaves nothing
+    let constraints = Constraints::from_compact(&["2A1:GGG"]).unwrap();
+    assert!(SumzleSolver::with_symbols(6, 99, symbols).search(&constraints).unwrap().is_empty());
+}
+
+#[test]
+fn greater_than_admits_a_negative_right_hand_side() {
+    let solver = SumzleSolver::new(6, 99);
+    let constraints = Constraints::from_compact(&["1-1>-5:GGGGGG"]).unwrap();
+    assert_eq!(solver.search(&constraints).unwrap(), vec!["1-1>-5".to_string()]);
+
+    let boards = [
+        (7, "1>0%1-5", ["4+3>6-8", "9A9+7>7", "8^6>7%1"], ["10%1>-2", "1!%1>-5", "0>0%1-2"]),
+        (6, "1%1>-5", ["4+3>-8", "9A9>71", "8^6>-1"], ["1-1>-5", "10!>-2", "1/0>-5"]),
+    ];
+    for (length, target, guesses, named) in boards {
+        let solver = SumzleSolver::with_rules(length, 30, RuleSet::game_compatible());
+        let results = solver.search(&constraints_for(&guesses, target)).unwrap();
+        for equation in named.into_iter().chain([target]) {
+            assert!(results.contains(&equation.to_string()), "{} missing", equation);
+        }
+
+        // Exactly the equations that would have given the same feedback
+        let consistent = |equation: &String| guesses.iter().all(|guess| score(guess, equation) == score(guess, target));
+        for equation in &results {
+            assert!(solver.is_valid_solution(equation), "{}", equation);
+            assert!(consistent(equation), "{}", equation);
+        }
+        // Searching every equation of length 7 takes too long in a debug build
+        if length == 6 {
+            let brute_force: Vec<String> = solver.search(&Constraints::default()).unwrap().into_iter().filter(consistent).collect();
+            assert_eq!(results, brute_force);
+        }
+    }
+}